@@ -11,22 +11,45 @@ describe_evm_tx() {
     echo ""
 }
 
-describe_miden_tx() {
-    echo "Miden tx with id $1 submited"
-    echo "You could find it in explorer https://testnet.midenscan.com/tx/$1"
-    echo ""
+init_miden_client() {
+    if [[ ! -e "miden-client.toml" ]]; then
+        $MIDEN_CLI init --network testnet
+    fi
 }
 
+# Imports the note file, then retries consuming the note with the given account until it is
+# committed on chain
+wait_and_consume_note() {
+    $MIDEN_CLI import "$1" || exit 1
+
+    for i in {1..5}; do
+        echo "Waiting for the note..."
+        sleep 90
+
+        $MIDEN_CLI sync
+        if $MIDEN_CLI consume-notes -a $2 $3; then
+            return 0
+        fi
+        echo "$4 still in progress"
+    done
+
+    return 1
+}
+
+# Note tools of the `miden` crate in this repository
+BRIDGE_CLI=${BRIDGE_CLI:-"cargo run -q --release --manifest-path $(dirname "$0")/miden/Cargo.toml --"}
+# Miden client managing the wallet, see "How to use"
+MIDEN_CLI=${MIDEN_CLI:-miden-bridge}
+
 SEPOLIA_RPC_URL="https://ethereum-sepolia-rpc.publicnode.com"
+SEPOLIA_CHAIN_ID=11155111
 EVM_MINIMUM_ALLOWED_BALANCE=10000000000000000
 USDC_EVM_ADDRESS="0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238"
-USDC_MIDEN_ADDRESS="0x4de3bc8d67731a2067af0fcc7a2e34"
+USDC_MIDEN_ADDRESS=${USDC_MIDEN_ADDRESS:-0x4de3bc8d67731a2067af0fcc7a2e34}
 BRIDGE_EVM_ADDRESS="0x0b03df1D4B3884b8987254D0C990342B571183AF"
 
 evm_to_miden() {
-    if [[ ! -e "miden-client.toml" ]]; then
-        miden-bridge init --network testnet
-    fi
+    init_miden_client
 
     prompt "Put your evm private key"
     privatekey=$prompt_result
@@ -43,11 +66,11 @@ evm_to_miden() {
         exit 1
     fi
     
-    receiver=$(miden-bridge account -d 2>/dev/null | grep -o "0x[0-9a-f]*")
+    receiver=$($MIDEN_CLI account -d 2>/dev/null | grep -o "0x[0-9a-f]*")
 
     if [[ -z $receiver ]]; then
-        miden-bridge new-wallet &>/dev/null
-        receiver=$(miden-bridge account -d 2>/dev/null | grep -o "0x[0-9a-f]*")
+        $MIDEN_CLI new-wallet &>/dev/null
+        receiver=$($MIDEN_CLI account -d 2>/dev/null | grep -o "0x[0-9a-f]*")
     fi
 
     echo "Miden receiver address: $receiver"
@@ -67,38 +90,57 @@ evm_to_miden() {
 
     sleep 40
 
-    recipient_response=$(miden-bridge recipient -a $receiver)
-    echo $recipient_response
-    recipient=$(echo "$recipient_response" | grep -o "0x[0-9a-f]*" | head -n 1)
-    serial_number=$(echo "$recipient_response" | grep -o "0x[0-9a-f]*" | tail -n 1)
+    recipient_response=$($BRIDGE_CLI generate --json --account-id $receiver) || exit 1
+    echo "$recipient_response"
+    recipient=$(echo "$recipient_response" | jq -r .recipient)
+    serial_number=$(echo "$recipient_response" | jq -r .serialNumber)
+
+    reconstruct_output=$($BRIDGE_CLI reconstruct --json --serial-number $serial_number --account-id $receiver --asset-amount $amount --faucet-id $USDC_MIDEN_ADDRESS --output "reconstructed_note.mno") || exit 1
+    reconstructed_note_id=$(echo "$reconstruct_output" | jq -r .noteId)
 
     echo "Bridge transaction generation"
     tx_id=$(cast publish --async -r $SEPOLIA_RPC_URL "$(cast mktx -r $SEPOLIA_RPC_URL --private-key $privatekey -f $address $BRIDGE_EVM_ADDRESS "bridgeAndCall(address,uint256,uint32,address,address,bytes,bool)" $USDC_EVM_ADDRESS $amount 9966 0x0000000000000000000000000000000000000000 0x0000000000000000000000000000000000000000 $recipient false)")
     describe_evm_tx $tx_id
 
-    for i in {1..5}; do
-        echo "Waiting for relayer..."
-        sleep 90
+    if ! wait_and_consume_note "reconstructed_note.mno" $receiver $reconstructed_note_id "Bridging"; then
+        echo "The relayer did not mint the note yet, consume $reconstructed_note_id later"
+        exit 1
+    fi
 
-        reconstruct_output=$(miden-bridge reconstruct --serial-number $serial_number --account-id $receiver --asset-amount $amount --faucet-id $USDC_MIDEN_ADDRESS 2>/dev/null)
+    echo "Bridging to miden finished!"
+}
 
-        if [[ "$?" == "0" ]]; then
-            reconstructed_note_id=$(echo "$reconstruct_output" | grep -o "Reconstructed note id: 0x[0-9a-f]*" | grep -o "0x[0-9a-f]*")
-            miden-bridge consume-notes -a $receiver $reconstructed_note_id
+miden_to_evm() {
+    init_miden_client
 
-            break
-        else
-            echo "Bridging still in progress"
-        fi
-    done
+    sender=$($MIDEN_CLI account -d 2>/dev/null | grep -o "0x[0-9a-f]*")
+    if [[ -z $sender ]]; then
+        echo "No default Miden account holding the USDC"
+        exit 1
+    fi
+    echo "Miden sender address: $sender"
 
-    echo "Bridging to miden finished!"
+    prompt "Put the receiver address"
+    receiver_address=$prompt_result
+
+    prompt "How much USDC you want to transfer?"
+    formated_amount=$prompt_result
+    amount=$(( $formated_amount * 1000000 ))
+
+    crosschain_response=$($BRIDGE_CLI crosschain --json --dest-chain $SEPOLIA_CHAIN_ID --dest-address $receiver_address --faucet-id $USDC_MIDEN_ADDRESS --amount $amount --sender $sender --output "crosschain_note.mno") || exit 1
+    echo "$crosschain_response"
+    crosschain_note_id=$(echo "$crosschain_response" | jq -r .noteId)
+
+    $MIDEN_CLI import-public $USDC_MIDEN_ADDRESS
+    if ! wait_and_consume_note "crosschain_note.mno" $USDC_MIDEN_ADDRESS $crosschain_note_id "Bridging"; then
+        exit 1
+    fi
+
+    echo "Bridging to EVM finished, the receiver gets the USDC once the relayer claims it"
 }
 
 mixing() {
-    if [[ ! -e "miden-client.toml" ]]; then
-        miden-bridge init --network testnet
-    fi
+    init_miden_client
 
     prompt "Put your evm private key"
     privatekey=$prompt_result
@@ -137,38 +179,24 @@ mixing() {
 
     sleep 40
 
-    recipient_response=$(miden-bridge recipient --note-type crosschain --dest-chain 11155111 --dest-address $receiver_address)
-    echo $recipient_response
-    bridge_note_serial_number=$(echo "$recipient_response" | grep -o "0x[0-9a-f]*" | head -n 1)
-    recipient=$(echo "$recipient_response" | grep -o "0x[0-9a-f]*" | head -n 2 | tail -n 1)
-    serial_number=$(echo "$recipient_response" | grep -o "0x[0-9a-f]*" | tail -n 1)
+    # The relayer mints the CROSSCHAIN note from the wrapper faucet, which then sends the asset on
+    crosschain_response=$($BRIDGE_CLI crosschain --json --dest-chain $SEPOLIA_CHAIN_ID --dest-address $receiver_address --faucet-id $USDC_MIDEN_ADDRESS --amount $amount --sender $USDC_MIDEN_ADDRESS --output "crosschain_note.mno") || exit 1
+    echo "$crosschain_response"
+    recipient=$(echo "$crosschain_response" | jq -r .recipient)
+    crosschain_note_id=$(echo "$crosschain_response" | jq -r .noteId)
 
     echo "Bridge transaction generation"
     tx_id=$(cast publish --async -r $SEPOLIA_RPC_URL "$(cast mktx -r $SEPOLIA_RPC_URL --private-key $privatekey -f $address $BRIDGE_EVM_ADDRESS "bridgeAndCall(address,uint256,uint32,address,address,bytes,bool)" $USDC_EVM_ADDRESS $amount 9966 0x0000000000000000000000000000000000000000 0x0000000000000000000000000000000000000000 $recipient false)")
     describe_evm_tx $tx_id
 
-    for i in {1..5}; do
-        echo "Waiting for relayer..."
+    $MIDEN_CLI import-public $USDC_MIDEN_ADDRESS
+    if wait_and_consume_note "crosschain_note.mno" $USDC_MIDEN_ADDRESS $crosschain_note_id "Mixing"; then
         sleep 90
 
-        reconstruct_output=$(miden-bridge mix --serial-number $serial_number --bridge-serial-number $bridge_note_serial_number --dest-chain 11155111 --dest-address $receiver_address --asset-amount $amount --faucet-id $USDC_MIDEN_ADDRESS 2>/dev/null)
-
-        if [[ "$?" == "0" ]]; then
-            reconstructed_note_id=$(echo "$reconstruct_output" | grep -o "Reconstructed note id: 0x[0-9a-f]*" | grep -o "0x[0-9a-f]*")
-            tx_id=$(echo "$reconstruct_output" | grep -o "Generated tx id: 0x[0-9a-f]*" | grep -o "0x[0-9a-f]*")
-            describe_miden_tx $tx_id
-
-            sleep 90
-
-            receiver_after_usdc_balance=$(cast balance $receiver_address --rpc-url $SEPOLIA_RPC_URL --erc20 $USDC_EVM_ADDRESS | awk '{print $1}')
-            formated_receiver_after_usdc_balance=$(echo "scale=2 ; $receiver_after_usdc_balance / 1000000" | bc )
-            echo "Receiver evm address: $receiver_address with $formated_receiver_after_usdc_balance USDC"
-
-            break
-        else
-            echo "Mixing still in progress"
-        fi
-    done
+        receiver_after_usdc_balance=$(cast balance $receiver_address --rpc-url $SEPOLIA_RPC_URL --erc20 $USDC_EVM_ADDRESS | awk '{print $1}')
+        formated_receiver_after_usdc_balance=$(echo "scale=2 ; $receiver_after_usdc_balance / 1000000" | bc )
+        echo "Receiver evm address: $receiver_address with $formated_receiver_after_usdc_balance USDC"
+    fi
 
     echo "Mixing finished!"
 }
@@ -183,9 +211,7 @@ direction=$prompt_result
 if [[ "$direction" == "1" ]]; then
     evm_to_miden
 elif [[ "$direction" == "2" ]]; then
-    echo "Miden to EVM"
-    echo "Not implemented yet!"
-    exit 1
+    miden_to_evm
 elif [[ "$direction" == "3" ]]; then
     mixing
 else
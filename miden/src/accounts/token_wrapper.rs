@@ -1,20 +1,37 @@
+use core::num::TryFromIntError;
+
 use miden_lib::{
-    account::{auth::{AuthRpoFalcon512Acl, AuthRpoFalcon512AclConfig}, faucets::BasicFungibleFaucet},
+    account::{
+        auth::{AuthRpoFalcon512Acl, AuthRpoFalcon512AclConfig},
+        faucets::{BasicFungibleFaucet, FungibleFaucetError},
+    },
     AuthScheme,
 };
 use miden_objects::{
     account::{
-        Account, AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot,
+        Account, AccountBuilder, AccountComponent, AccountId, AccountStorageMode, AccountType,
+        StorageSlot,
     },
     asset::TokenSymbol,
+    crypto::dsa::rpo_falcon512::PublicKey,
     note::NoteTag,
     utils::sync::LazyLock,
-    AccountError, Felt, Word,
+    AccountError, Felt, StarkField, TokenSymbolError, Word,
 };
+use thiserror::Error;
 
 use crate::accounts::components::token_wrapper_account_library;
 
+/// Version of the wrapper code and of the CROSSCHAIN note script calling its `bridge` procedure.
+///
+/// A CROSSCHAIN note calls the procedure by its MAST root, so it can only be consumed by a faucet
+/// of the same version. Version 2 reads the exit payload, calldata included, from the inputs of
+/// the note instead of the stack, faucets of version 1 must be redeployed.
+pub const TOKEN_WRAPPER_VERSION: u32 = 2;
+
 const BRIDGE_TAG_USECASE: u16 = 12354;
+/// Storage slot reserved by the kernel in faucet accounts, holding `[0, 0, 0, total_issuance]`.
+const FAUCET_RESERVED_SLOT: u8 = 0;
 const BRIDGE_TAG: LazyLock<NoteTag> =
     LazyLock::new(|| NoteTag::for_local_use_case(BRIDGE_TAG_USECASE, 0).unwrap());
 
@@ -22,6 +39,39 @@ pub fn bridge_note_tag() -> NoteTag {
     BRIDGE_TAG.clone()
 }
 
+/// Code commitment of the wrapper faucets of [`TOKEN_WRAPPER_VERSION`]. The code does not depend
+/// on the seed, key, metadata or origin of a faucet, so any faucet built by this crate has it.
+static TOKEN_WRAPPER_CODE_COMMITMENT: LazyLock<Word> = LazyLock::new(|| {
+    let (account, _) = create_token_wrapper_account(
+        [0; 32],
+        TokenSymbol::new("WRAP").expect("Reference symbol is valid"),
+        0,
+        Felt::new(1),
+        0,
+        [Felt::new(0); 3],
+        AccountStorageMode::Public,
+        AuthScheme::RpoFalcon512 { pub_key: PublicKey::new(Word::empty()) },
+    )
+    .expect("Reference token wrapper account is valid");
+
+    account.code().commitment()
+});
+
+#[derive(Error, Debug)]
+pub enum TokenWrapperError {
+    #[error("account {0} does not contain the {1} component")]
+    MissingComponent(AccountId, &'static str),
+    #[error(transparent)]
+    AccountError(#[from] AccountError),
+    #[error(transparent)]
+    TokenSymbolError(#[from] TokenSymbolError),
+    #[error(transparent)]
+    FungibleFaucetError(#[from] FungibleFaucetError),
+    #[error(transparent)]
+    NumberOverflowError(#[from] TryFromIntError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct TokenWrapperAccount {
     origin_network: u64,
     origin_address: [Felt; 3],
@@ -35,6 +85,103 @@ impl TokenWrapperAccount {
     pub fn new(origin_network: u64, origin_address: [Felt; 3]) -> Self {
         Self { origin_network, origin_address }
     }
+
+    // ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    pub fn origin_network(&self) -> u64 {
+        self.origin_network
+    }
+
+    pub fn origin_address(&self) -> [Felt; 3] {
+        self.origin_address
+    }
+
+    /// Reads the origin network and address of the wrapped token from the storage of `account`.
+    ///
+    /// The slot is located through the storage offset of the wrapper component procedures, so it
+    /// does not depend on the auth component or the order of the components.
+    pub fn origin_info(account: &Account) -> Result<Self, TokenWrapperError> {
+        let slot = component_storage_offset(account, "token wrapper", |root| {
+            token_wrapper_account_library()
+                .mast_forest()
+                .procedure_digests()
+                .any(|digest| digest == root)
+        })?;
+
+        Ok(Self::decode_origin_slot(account.storage().get_item(slot)?))
+    }
+
+    /// Whether `account` runs the wrapper code of [`TOKEN_WRAPPER_VERSION`], the only one able to
+    /// consume the CROSSCHAIN notes built by this crate.
+    pub fn has_current_code(account: &Account) -> bool {
+        account.code().commitment() == *TOKEN_WRAPPER_CODE_COMMITMENT
+    }
+
+    /// Reads the token metadata of the basic fungible faucet component of `account`.
+    pub fn token_metadata(account: &Account) -> Result<BasicFungibleFaucet, TokenWrapperError> {
+        let slot = component_storage_offset(account, "basic fungible faucet", |root| {
+            root == BasicFungibleFaucet::distribute_digest()
+        })?;
+
+        decode_metadata_slot(account.storage().get_item(slot)?)
+    }
+
+    /// Reads the public key the auth component of `account` verifies signatures against.
+    ///
+    /// The auth procedure is always the first procedure of the account code, and its component
+    /// keeps the key in its first storage slot.
+    pub fn auth_public_key(account: &Account) -> Result<Word, TokenWrapperError> {
+        let slot = account
+            .code()
+            .procedures()
+            .first()
+            .map(|procedure| procedure.storage_offset())
+            .ok_or(TokenWrapperError::MissingComponent(account.id(), "auth"))?;
+
+        Ok(account.storage().get_item(slot)?)
+    }
+
+    /// Reads the total issuance of the faucet, kept by the kernel in its reserved storage slot
+    /// and updated by every mint and burn.
+    pub fn total_issuance(account: &Account) -> Result<u64, TokenWrapperError> {
+        Ok(decode_issuance_slot(account.storage().get_item(FAUCET_RESERVED_SLOT)?))
+    }
+
+    fn decode_origin_slot(slot: Word) -> Self {
+        Self::new(slot[0].as_int(), [slot[3], slot[2], slot[1]])
+    }
+}
+
+/// Returns the first storage slot of the component whose procedures match `is_component_root`.
+fn component_storage_offset(
+    account: &Account,
+    component: &'static str,
+    is_component_root: impl Fn(Word) -> bool,
+) -> Result<u8, TokenWrapperError> {
+    account
+        .code()
+        .procedures()
+        .iter()
+        .find(|procedure| is_component_root(*procedure.mast_root()))
+        .map(|procedure| procedure.storage_offset())
+        .ok_or(TokenWrapperError::MissingComponent(account.id(), component))
+}
+
+/// Decodes the `[0, 0, 0, total_issuance]` reserved slot of a fungible faucet.
+fn decode_issuance_slot(slot: Word) -> u64 {
+    slot[3].as_int()
+}
+
+/// Decodes the `[max_supply, decimals, symbol, 0]` metadata slot of the basic fungible faucet.
+fn decode_metadata_slot(slot: Word) -> Result<BasicFungibleFaucet, TokenWrapperError> {
+    let [max_supply, decimals, symbol, _]: [Felt; 4] = slot.into();
+
+    Ok(BasicFungibleFaucet::new(
+        TokenSymbol::try_from(symbol)?,
+        u8::try_from(decimals.as_int())?,
+        max_supply,
+    )?)
 }
 
 impl From<TokenWrapperAccount> for AccountComponent {
@@ -129,3 +276,77 @@ pub fn create_token_wrapper_account_builder(
         account_storage_mode,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use miden_lib::AuthScheme;
+    use miden_objects::{
+        account::AccountStorageMode, asset::TokenSymbol, crypto::dsa::rpo_falcon512::PublicKey,
+        utils::parse_hex_string_as_word, Felt, Word,
+    };
+
+    use super::{
+        create_token_wrapper_account, decode_issuance_slot, decode_metadata_slot,
+        TokenWrapperAccount,
+    };
+    use crate::utils::{evm_address_to_felts, felts_to_evm_addresses};
+
+    #[test]
+    fn should_decode_origin_slot() {
+        let slot = parse_hex_string_as_word(
+            "0x8238010000000000fd9ae61e000000008e784c5a1efa36822f476def8a5e8141",
+        )
+        .unwrap();
+        let origin = TokenWrapperAccount::decode_origin_slot(Word::from(slot));
+        let origin_address = felts_to_evm_addresses(origin.origin_address()).unwrap();
+
+        assert_eq!(origin.origin_network(), 80002);
+        assert_eq!(
+            origin_address.to_checksum(None).to_lowercase(),
+            "0x2f476def8a5e81418e784c5a1efa3682fd9ae61e"
+        );
+    }
+
+    #[test]
+    fn should_decode_metadata_slot() {
+        let slot = parse_hex_string_as_word(
+            "0xfeffffff00000000060000000000000013340000000000000000000000000000",
+        )
+        .unwrap();
+        let metadata = decode_metadata_slot(Word::from(slot)).unwrap();
+
+        assert_eq!(metadata.decimals(), 6);
+        assert_eq!(metadata.symbol().to_string(), Ok("AAATST".to_string()));
+    }
+
+    #[test]
+    fn should_decode_issuance_slot() {
+        let slot = parse_hex_string_as_word(
+            "0x000000000000000000000000000000000000000000000000e803000000000000",
+        )
+        .unwrap();
+
+        assert_eq!(decode_issuance_slot(Word::from(slot)), 1000);
+    }
+
+    #[test]
+    fn should_read_the_code_and_key_of_a_wrapper_faucet() {
+        let pub_key = Word::from([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]);
+        let (account, _) = create_token_wrapper_account(
+            [7; 32],
+            TokenSymbol::new("USDC").unwrap(),
+            6,
+            Felt::new(1_000_000),
+            11155111,
+            evm_address_to_felts("0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238").unwrap(),
+            AccountStorageMode::Public,
+            AuthScheme::RpoFalcon512 { pub_key: PublicKey::new(pub_key) },
+        )
+        .unwrap();
+
+        assert!(TokenWrapperAccount::has_current_code(&account));
+        assert_eq!(TokenWrapperAccount::auth_public_key(&account).unwrap(), pub_key);
+    }
+}
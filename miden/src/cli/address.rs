@@ -0,0 +1,54 @@
+use core::fmt;
+
+use clap::Args;
+use miden_bridge::utils::{evm_address_to_felts, felts_to_evm_addresses};
+use miden_objects::Felt;
+use serde::Serialize;
+
+use crate::{cli::print_output, CliError};
+
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+pub struct DecodeAddressCmd {
+    /// EVM address to encode into note input felts
+    #[arg(long)]
+    evm: Option<String>,
+
+    /// Three note input felts to decode into an EVM address
+    #[arg(long, num_args = 3, value_names = ["FELT1", "FELT2", "FELT3"])]
+    felts: Option<Vec<u64>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DecodeAddressOutput {
+    address: String,
+    felts: [u64; 3],
+}
+
+impl fmt::Display for DecodeAddressOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "EVM address: {}", self.address)?;
+        writeln!(f, "Felts: [{}, {}, {}]", self.felts[0], self.felts[1], self.felts[2])
+    }
+}
+
+impl DecodeAddressCmd {
+    pub fn execute(&self, json: bool) -> Result<(), CliError> {
+        let felts = match (&self.evm, &self.felts) {
+            (Some(address), _) => evm_address_to_felts(address)?,
+            (None, Some(felts)) => [Felt::new(felts[0]), Felt::new(felts[1]), Felt::new(felts[2])],
+            (None, None) => unreachable!("clap requires one of the arguments"),
+        };
+
+        let address = felts_to_evm_addresses(felts)?;
+
+        print_output(
+            &DecodeAddressOutput {
+                address: address.to_checksum(None),
+                felts: felts.map(|felt| felt.as_int()),
+            },
+            json,
+        )
+    }
+}
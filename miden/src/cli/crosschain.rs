@@ -0,0 +1,158 @@
+use core::fmt;
+use std::{fs::File, io::Write, path::PathBuf};
+
+use clap::Args;
+use miden_bridge::{
+    codec::CrosschainInputs, notes::crosschain::new_crosschain_note,
+    utils::evm_address_to_felts,
+};
+use miden_objects::{
+    account::AccountId,
+    crypto::{rand::FeltRng, utils::word_to_hex},
+    note::{NoteDetails, NoteFile, NoteTag},
+    utils::Serializable,
+};
+use serde::Serialize;
+
+use crate::{
+    cli::{
+        print_output,
+        serials::{serial_number_rng, SerialRegistry, DEFAULT_SERIALS_FILE},
+    },
+    CliError,
+};
+
+#[derive(Args, Debug)]
+pub struct CrosschainCmd {
+    /// Destination EVM chain id
+    #[arg(short = 'c', long)]
+    dest_chain: u64,
+
+    /// Receiver address on the destination chain
+    #[arg(short = 'a', long)]
+    dest_address: String,
+
+    /// Wrapper faucet of the bridged asset
+    #[arg(short, long)]
+    faucet_id: String,
+
+    /// Bridged amount in the asset base units
+    #[arg(short = 'm', long)]
+    amount: u64,
+
+    /// Miden account sending the asset
+    #[arg(short, long)]
+    sender: String,
+
+    /// Unix timestamp before which the note can not be consumed
+    #[arg(long)]
+    unblock_timestamp: Option<u32>,
+
+    /// Hex encoded calldata executed on the destination chain after the transfer
+    #[arg(long, requires = "call_address")]
+    calldata: Option<String>,
+
+    /// Contract called with the calldata on the destination chain
+    #[arg(long)]
+    call_address: Option<String>,
+
+    /// Deterministic seed for the serial numbers. Only meant for tests, seeded serials are not
+    /// recorded in the serials file
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// File tracking the already emitted serial numbers
+    #[arg(long, default_value = DEFAULT_SERIALS_FILE)]
+    serials_file: PathBuf,
+
+    /// Path of the resulting note file
+    #[arg(short, long, default_value = "crosschain_note.mno")]
+    output: PathBuf,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CrosschainOutput {
+    note_id: String,
+    recipient: String,
+    serial_number: String,
+    bridge_serial_number: String,
+    output: PathBuf,
+}
+
+impl fmt::Display for CrosschainOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Crosschain note id: {}", self.note_id)?;
+        writeln!(f, "Recipient: {}", self.recipient)?;
+        writeln!(f, "Serial number: {}", self.serial_number)?;
+        writeln!(f, "BRIDGE serial number: {}", self.bridge_serial_number)?;
+        writeln!(f, "Note file: {}", self.output.display())
+    }
+}
+
+impl CrosschainCmd {
+    pub fn execute(&self, json: bool) -> Result<(), CliError> {
+        let faucet_id = AccountId::from_hex(&self.faucet_id)?;
+        let sender = AccountId::from_hex(&self.sender)?;
+        let dest_address = evm_address_to_felts(&self.dest_address)?;
+        let call_address = self.call_address.as_ref().map(evm_address_to_felts).transpose()?;
+        let calldata = match &self.calldata {
+            Some(calldata) => hex::decode(calldata.trim_start_matches("0x"))
+                .map_err(|e| CliError::BytesHexDecodingError(e.to_string()))?,
+            None => Vec::new(),
+        };
+
+        let mut rng = serial_number_rng(self.seed)?;
+        let serial_number = rng.draw_word();
+        let bridge_serial_number = rng.draw_word();
+
+        // The note is consumed by the wrapper faucet, which burns the asset and emits the BRIDGE
+        // note watched by the relayer
+        let tag = NoteTag::from_account_id(faucet_id);
+
+        let inputs = CrosschainInputs {
+            output_serial_number: bridge_serial_number,
+            dest_chain: self.dest_chain,
+            dest_address,
+            unblock_timestamp: self.unblock_timestamp,
+            calldata,
+            call_address,
+        };
+
+        let note = new_crosschain_note(
+            serial_number,
+            &inputs,
+            faucet_id,
+            self.amount,
+            sender,
+            tag,
+        )
+        .map_err(CliError::BuildExportableNoteError)?;
+
+        let note_file = NoteFile::NoteDetails {
+            details: NoteDetails::from(note.clone()),
+            after_block_num: 0.into(),
+            tag: Some(tag),
+        };
+
+        let mut file = File::create(&self.output)?;
+        file.write_all(&note_file.to_bytes())?;
+
+        // Recorded once the note file exists, so a failed write does not burn the serials
+        if self.seed.is_none() {
+            SerialRegistry::new(&self.serials_file)
+                .register(&[serial_number, bridge_serial_number])?;
+        }
+
+        print_output(
+            &CrosschainOutput {
+                note_id: note.id().to_hex(),
+                recipient: note.recipient().digest().to_hex(),
+                serial_number: word_to_hex(&serial_number)?,
+                bridge_serial_number: word_to_hex(&bridge_serial_number)?,
+                output: self.output.clone(),
+            },
+            json,
+        )
+    }
+}
@@ -0,0 +1,67 @@
+use core::fmt;
+use std::path::PathBuf;
+
+use clap::Args;
+use miden_lib::note::utils::build_p2id_recipient;
+use miden_objects::{account::AccountId, crypto::utils::word_to_hex, Word};
+use serde::Serialize;
+
+use crate::{
+    cli::{
+        print_output,
+        serials::{draw_serial_number, SerialRegistry, DEFAULT_SERIALS_FILE},
+    },
+    CliError,
+};
+
+#[derive(Args, Debug)]
+pub struct GenerateCmd {
+    /// Miden account receiving the bridged asset
+    #[arg(short = 'a', long)]
+    account_id: String,
+
+    /// Deterministic seed for the serial number. Only meant for tests, seeded serials are not
+    /// recorded in the serials file
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// File tracking the already emitted serial numbers
+    #[arg(long, default_value = DEFAULT_SERIALS_FILE)]
+    serials_file: PathBuf,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateOutput {
+    recipient: String,
+    serial_number: String,
+}
+
+impl fmt::Display for GenerateOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Recipient: {}", self.recipient)?;
+        writeln!(f, "Serial number: {}", self.serial_number)
+    }
+}
+
+impl GenerateCmd {
+    pub fn execute(&self, json: bool) -> Result<(), CliError> {
+        let serial_number: Word = draw_serial_number(self.seed)?;
+        let receiver = AccountId::from_hex(&self.account_id)?;
+
+        let recipient = build_p2id_recipient(receiver, serial_number)
+            .map_err(CliError::BuildNoteRecipientError)?;
+
+        if self.seed.is_none() {
+            SerialRegistry::new(&self.serials_file).register(&[serial_number])?;
+        }
+
+        print_output(
+            &GenerateOutput {
+                recipient: recipient.digest().to_hex(),
+                serial_number: word_to_hex(&serial_number)?,
+            },
+            json,
+        )
+    }
+}
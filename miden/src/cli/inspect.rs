@@ -0,0 +1,137 @@
+use core::fmt;
+use std::{fs, path::PathBuf};
+
+use clap::Args;
+use miden_objects::{
+    asset::Asset,
+    crypto::utils::word_to_hex,
+    note::{NoteDetails, NoteFile},
+    utils::Deserializable,
+};
+use serde::Serialize;
+
+use crate::{cli::print_output, CliError};
+
+#[derive(Args, Debug)]
+pub struct InspectNoteCmd {
+    /// Path of the `.mno` note file
+    path: PathBuf,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NoteAssetOutput {
+    faucet_id: String,
+    amount: Option<u64>,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct InspectNoteOutput {
+    kind: &'static str,
+    note_id: String,
+    after_block_num: Option<u32>,
+    inclusion_block_num: Option<u32>,
+    tag: Option<u32>,
+    sender: Option<String>,
+    recipient: Option<String>,
+    serial_number: Option<String>,
+    script_root: Option<String>,
+    inputs: Vec<u64>,
+    assets: Vec<NoteAssetOutput>,
+}
+
+impl InspectNoteOutput {
+    fn with_details(self, details: &NoteDetails) -> Result<Self, CliError> {
+        Ok(Self {
+            note_id: details.id().to_hex(),
+            recipient: Some(details.recipient().digest().to_hex()),
+            serial_number: Some(word_to_hex(&details.serial_num())?),
+            script_root: Some(details.script().root().to_hex()),
+            inputs: details.inputs().values().iter().map(|felt| felt.as_int()).collect(),
+            assets: details
+                .assets()
+                .iter()
+                .map(|asset| match asset {
+                    Asset::Fungible(asset) => NoteAssetOutput {
+                        faucet_id: asset.faucet_id().to_hex(),
+                        amount: Some(asset.amount()),
+                    },
+                    Asset::NonFungible(asset) => NoteAssetOutput {
+                        faucet_id: asset.faucet_id_prefix().to_hex(),
+                        amount: None,
+                    },
+                })
+                .collect(),
+            ..self
+        })
+    }
+}
+
+impl fmt::Display for InspectNoteOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Note file kind: {}", self.kind)?;
+        writeln!(f, "Note id: {}", self.note_id)?;
+        if let Some(after_block_num) = self.after_block_num {
+            writeln!(f, "After block: {after_block_num}")?;
+        }
+        if let Some(inclusion_block_num) = self.inclusion_block_num {
+            writeln!(f, "Included in block: {inclusion_block_num}")?;
+        }
+        if let Some(tag) = self.tag {
+            writeln!(f, "Tag: {tag}")?;
+        }
+        if let Some(sender) = &self.sender {
+            writeln!(f, "Sender: {sender}")?;
+        }
+        if let Some(recipient) = &self.recipient {
+            writeln!(f, "Recipient: {recipient}")?;
+        }
+        if let Some(serial_number) = &self.serial_number {
+            writeln!(f, "Serial number: {serial_number}")?;
+        }
+        if let Some(script_root) = &self.script_root {
+            writeln!(f, "Script root: {script_root}")?;
+        }
+        if !self.inputs.is_empty() {
+            let inputs: Vec<String> = self.inputs.iter().map(u64::to_string).collect();
+            writeln!(f, "Inputs: [{}]", inputs.join(", "))?;
+        }
+        for asset in &self.assets {
+            match asset.amount {
+                Some(amount) => writeln!(f, "Asset: {amount} of {}", asset.faucet_id)?,
+                None => writeln!(f, "Asset: non-fungible of {}", asset.faucet_id)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl InspectNoteCmd {
+    pub fn execute(&self, json: bool) -> Result<(), CliError> {
+        let note_file = NoteFile::read_from_bytes(&fs::read(&self.path)?)?;
+
+        let output = match note_file {
+            NoteFile::NoteId(note_id) => {
+                InspectNoteOutput { kind: "id", note_id: note_id.to_hex(), ..Default::default() }
+            },
+            NoteFile::NoteDetails { details, after_block_num, tag } => InspectNoteOutput {
+                kind: "details",
+                after_block_num: Some(after_block_num.as_u32()),
+                tag: tag.map(u32::from),
+                ..Default::default()
+            }
+            .with_details(&details)?,
+            NoteFile::NoteWithProof(note, proof) => InspectNoteOutput {
+                kind: "with-proof",
+                inclusion_block_num: Some(proof.location().block_num().as_u32()),
+                tag: Some(u32::from(note.metadata().tag())),
+                sender: Some(note.metadata().sender().to_hex()),
+                ..Default::default()
+            }
+            .with_details(&NoteDetails::from(note))?,
+        };
+
+        print_output(&output, json)
+    }
+}
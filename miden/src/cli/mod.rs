@@ -0,0 +1,23 @@
+use core::fmt;
+
+use serde::Serialize;
+
+use crate::CliError;
+
+pub mod address;
+pub mod crosschain;
+pub mod generate;
+pub mod inspect;
+pub mod reconstruct;
+pub mod serials;
+
+/// Prints the command output either in the human readable form or as JSON.
+pub fn print_output<T: Serialize + fmt::Display>(output: &T, json: bool) -> Result<(), CliError> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(output)?);
+    } else {
+        print!("{output}");
+    }
+
+    Ok(())
+}
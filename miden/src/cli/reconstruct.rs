@@ -0,0 +1,104 @@
+use core::fmt;
+use std::{fs::File, io::Write, path::PathBuf};
+
+use clap::Args;
+use miden_bridge::notes::BRIDGE_USECASE;
+use miden_lib::note::utils::build_p2id_recipient;
+use miden_objects::{
+    account::AccountId,
+    asset::{Asset, FungibleAsset},
+    note::{
+        Note, NoteAssets, NoteDetails, NoteExecutionHint, NoteFile, NoteMetadata, NoteTag, NoteType,
+    },
+    utils::{parse_hex_string_as_word, Serializable},
+    Felt, FieldElement, Word,
+};
+use serde::Serialize;
+
+use crate::{cli::print_output, CliError};
+
+#[derive(Args, Debug)]
+pub struct ReconstructCmd {
+    /// Serial number printed by `generate`
+    #[arg(long)]
+    serial_number: String,
+
+    /// Miden account receiving the bridged asset
+    #[arg(short = 'a', long)]
+    account_id: String,
+
+    /// Bridged amount in the asset base units
+    #[arg(long)]
+    asset_amount: u64,
+
+    /// Wrapper faucet of the bridged asset
+    #[arg(long)]
+    faucet_id: String,
+
+    /// Path of the resulting note file
+    #[arg(short, long, default_value = "reconstructed_note.mno")]
+    output: PathBuf,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReconstructOutput {
+    note_id: String,
+    output: PathBuf,
+}
+
+impl fmt::Display for ReconstructOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Reconstructed note id: {}", self.note_id)?;
+        writeln!(f, "Note file: {}", self.output.display())
+    }
+}
+
+impl ReconstructCmd {
+    pub fn execute(&self, json: bool) -> Result<(), CliError> {
+        let serial_number = parse_hex_string_as_word(&self.serial_number)
+            .map_err(|e| CliError::BytesHexDecodingError(e.to_string()))?;
+
+        let receiver = AccountId::from_hex(&self.account_id)?;
+
+        let recipient = build_p2id_recipient(receiver, Word::new(serial_number))
+            .map_err(CliError::BuildNoteRecipientError)?;
+
+        let faucet_id = AccountId::from_hex(&self.faucet_id)?;
+
+        let asset = FungibleAsset::new(faucet_id, self.asset_amount)?;
+
+        let note = Note::new(
+            NoteAssets::new(vec![Asset::from(asset)])
+                .map_err(CliError::BuildExportableNoteError)?,
+            NoteMetadata::new(
+                faucet_id,
+                NoteType::Private,
+                NoteTag::for_local_use_case(1, 0).map_err(CliError::BuildExportableNoteError)?,
+                NoteExecutionHint::Always,
+                Felt::ZERO,
+            )
+            .map_err(CliError::BuildExportableNoteError)?,
+            recipient,
+        );
+
+        let note_details = NoteDetails::new(note.assets().clone(), note.recipient().clone());
+
+        let note_file = NoteFile::NoteDetails {
+            details: note_details,
+            after_block_num: 0.into(),
+            tag: Some(
+                NoteTag::for_local_use_case(BRIDGE_USECASE, 0)
+                    .map_err(CliError::BuildExportableNoteError)?,
+            ),
+        };
+
+        let mut file = File::create(&self.output)?;
+        file.write_all(&note_file.to_bytes())?;
+
+        print_output(
+            &ReconstructOutput { note_id: note.id().to_hex(), output: self.output.clone() },
+            json,
+        )
+    }
+}
@@ -0,0 +1,171 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use miden_objects::{
+    crypto::{
+        rand::{FeltRng, RpoRandomCoin},
+        utils::word_to_hex,
+    },
+    Felt, FieldElement, Word,
+};
+use rand::{rngs::OsRng, TryRngCore};
+
+use crate::CliError;
+
+/// File in the working directory keeping the serial numbers emitted by `generate`.
+pub const DEFAULT_SERIALS_FILE: &str = "emitted_serials.txt";
+
+/// How long [`SerialRegistry::register`] waits for a concurrent run to release the registry.
+const REGISTRY_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Creates the generator for note serial numbers.
+///
+/// The generator is seeded from OS entropy unless a `seed` is given, which makes the output
+/// deterministic and is only meant for tests.
+pub fn serial_number_rng(seed: Option<u64>) -> Result<RpoRandomCoin, CliError> {
+    let coin_seed = match seed {
+        Some(seed) => [Felt::new(seed), Felt::ZERO, Felt::ZERO, Felt::ZERO],
+        None => {
+            let mut entropy = [0u8; 32];
+            OsRng
+                .try_fill_bytes(&mut entropy)
+                .map_err(|e| CliError::EntropyError(e.to_string()))?;
+
+            let mut coin_seed = [Felt::ZERO; 4];
+            for (felt, chunk) in coin_seed.iter_mut().zip(entropy.chunks_exact(8)) {
+                *felt = Felt::new(u64::from_le_bytes(chunk.try_into().expect("8 bytes chunk")));
+            }
+            coin_seed
+        },
+    };
+
+    Ok(RpoRandomCoin::new(Word::new(coin_seed)))
+}
+
+/// Draws a single note serial number, see [`serial_number_rng`].
+pub fn draw_serial_number(seed: Option<u64>) -> Result<Word, CliError> {
+    Ok(serial_number_rng(seed)?.draw_word())
+}
+
+/// Local registry of the serial numbers emitted by the CLI, one hex encoded word per line.
+pub struct SerialRegistry {
+    path: PathBuf,
+}
+
+impl SerialRegistry {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self { path: path.as_ref().to_path_buf() }
+    }
+
+    pub fn contains(&self, serial_number: &Word) -> Result<bool, CliError> {
+        let serial_number = word_to_hex(serial_number)?;
+
+        match fs::read_to_string(&self.path) {
+            Ok(content) => Ok(content.lines().any(|line| line.trim() == serial_number)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(CliError::from(e)),
+        }
+    }
+
+    /// Records the serial numbers, failing without recording any of them if one was already
+    /// emitted.
+    ///
+    /// The check and the write happen under an exclusive lock file next to the registry, so
+    /// concurrent runs can not both record the same serial number.
+    pub fn register(&self, serial_numbers: &[Word]) -> Result<(), CliError> {
+        let _lock = RegistryLock::acquire(&self.path)?;
+
+        let mut lines = String::new();
+        for serial_number in serial_numbers {
+            if self.contains(serial_number)? {
+                return Err(CliError::SerialNumberReuseError(word_to_hex(serial_number)?));
+            }
+            lines.push_str(&word_to_hex(serial_number)?);
+            lines.push('\n');
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(lines.as_bytes())?;
+
+        Ok(())
+    }
+}
+
+/// Lock file held while the registry is checked and written, removed on drop.
+struct RegistryLock {
+    path: PathBuf,
+}
+
+impl RegistryLock {
+    fn acquire(registry: &Path) -> Result<Self, CliError> {
+        let mut path = registry.as_os_str().to_owned();
+        path.push(".lock");
+        let path = PathBuf::from(path);
+
+        let deadline = Instant::now() + REGISTRY_LOCK_TIMEOUT;
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(Self { path }),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists && Instant::now() < deadline => {
+                    thread::sleep(Duration::from_millis(50));
+                },
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    return Err(CliError::from(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("serial registry is locked by {}", path.display()),
+                    )));
+                },
+                Err(e) => return Err(CliError::from(e)),
+            }
+        }
+    }
+}
+
+impl Drop for RegistryLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::crypto::rand::FeltRng;
+
+    use super::{draw_serial_number, serial_number_rng, SerialRegistry};
+    use crate::CliError;
+
+    #[test]
+    fn should_draw_distinct_serials_from_entropy() {
+        assert_ne!(draw_serial_number(None).unwrap(), draw_serial_number(None).unwrap());
+        assert_eq!(draw_serial_number(Some(7)).unwrap(), draw_serial_number(Some(7)).unwrap());
+
+        let mut rng = serial_number_rng(Some(7)).unwrap();
+        assert_ne!(rng.draw_word(), rng.draw_word());
+    }
+
+    #[test]
+    fn should_refuse_serial_reuse() {
+        let path = std::env::temp_dir().join(format!("serials-{}.txt", rand::random::<u64>()));
+        let registry = SerialRegistry::new(&path);
+        let serial_number = draw_serial_number(Some(1)).unwrap();
+
+        registry.register(&[serial_number]).unwrap();
+        assert!(registry.contains(&serial_number).unwrap());
+
+        let fresh = draw_serial_number(Some(2)).unwrap();
+        assert!(matches!(
+            registry.register(&[fresh, serial_number]),
+            Err(CliError::SerialNumberReuseError(_))
+        ));
+        assert!(!registry.contains(&fresh).unwrap());
+
+        registry.register(&[fresh]).unwrap();
+        assert!(registry.contains(&fresh).unwrap());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,309 @@
+//! Typed layouts of the bridge note inputs.
+//!
+//! CROSSCHAIN note inputs, consumed by the wrapper faucet:
+//! `[OUTPUT_SERIAL_NUM, dest_chain, dest_addr(3), unblock_timestamp, calldata_bytes_length,
+//!   calldata.., call_addr(3)]`
+//!
+//! BRIDGE note inputs, emitted by the wrapper `bridge` procedure:
+//! `[ASSET, dest_chain, dest_addr(3), calldata_bytes_length, calldata.., call_addr(3)]`
+//!
+//! Addresses are stored with their felts in reverse order and calldata is packed with
+//! [calldata_to_felts]. The BRIDGE note serial number is the `OUTPUT_SERIAL_NUM` word as is.
+
+use alloc::vec::Vec;
+
+use miden_objects::{
+    asset::{Asset, FungibleAsset},
+    note::NoteInputs,
+    AssetError, Felt, FieldElement, NoteError, StarkField, Word,
+};
+use thiserror::Error;
+
+use crate::utils::{calldata_felts_len, calldata_to_felts, felts_to_calldata, CalldataFormatError};
+
+/// Number of CROSSCHAIN note inputs without calldata.
+pub const CROSSCHAIN_MIN_INPUTS: usize = 13;
+
+/// Number of BRIDGE note inputs without calldata.
+pub const BRIDGE_MIN_INPUTS: usize = 12;
+
+const CROSSCHAIN_CALLDATA_OFFSET: usize = 9;
+const BRIDGE_CALLDATA_OFFSET: usize = 8;
+
+#[derive(Error, Debug)]
+pub enum CodecError {
+    #[error("expected {expected} note inputs, got {actual}")]
+    InputsLengthMismatch { expected: usize, actual: usize },
+    #[error("unblock timestamp {0} does not fit into u32")]
+    TimestampOutOfRange(u64),
+    #[error("bridged asset is not fungible")]
+    NonFungibleAsset,
+    #[error(transparent)]
+    CalldataFormatError(#[from] CalldataFormatError),
+    #[error(transparent)]
+    AssetError(#[from] AssetError),
+}
+
+/// Inputs of the CROSSCHAIN note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrosschainInputs {
+    /// Serial number of the emitted BRIDGE note
+    pub output_serial_number: Word,
+    pub dest_chain: u64,
+    pub dest_address: [Felt; 3],
+    /// Timestamp before which the note can not be consumed
+    pub unblock_timestamp: Option<u32>,
+    pub calldata: Vec<u8>,
+    /// Contract called with the calldata on the destination chain
+    pub call_address: Option<[Felt; 3]>,
+}
+
+impl CrosschainInputs {
+    pub fn to_elements(&self) -> Vec<Felt> {
+        let mut elements = Vec::from(self.output_serial_number.as_elements());
+        elements.push(Felt::new(self.dest_chain));
+        elements.extend(encode_address(self.dest_address));
+        elements.push(Felt::new(self.unblock_timestamp.unwrap_or(0) as u64));
+        encode_call(&mut elements, &self.calldata, self.call_address);
+        elements
+    }
+
+    pub fn to_note_inputs(&self) -> Result<NoteInputs, NoteError> {
+        NoteInputs::new(self.to_elements())
+    }
+
+    /// Returns the inputs of the BRIDGE note emitted when the CROSSCHAIN note burning `asset` is
+    /// consumed, as built by the wrapper `bridge` procedure.
+    pub fn bridge_event_inputs(&self, asset: FungibleAsset) -> BridgeEventInputs {
+        BridgeEventInputs {
+            asset,
+            dest_chain: self.dest_chain,
+            dest_address: self.dest_address,
+            calldata: self.calldata.clone(),
+            call_address: self.call_address,
+        }
+    }
+}
+
+impl TryFrom<&[Felt]> for CrosschainInputs {
+    type Error = CodecError;
+
+    /// Decodes the CROSSCHAIN inputs, which the note script requires to have no trailing values.
+    fn try_from(values: &[Felt]) -> Result<Self, Self::Error> {
+        check_inputs_len(values, CROSSCHAIN_MIN_INPUTS)?;
+        let (calldata, call_address, end) = decode_call(values, CROSSCHAIN_CALLDATA_OFFSET)?;
+        if end != values.len() {
+            return Err(CodecError::InputsLengthMismatch { expected: end, actual: values.len() });
+        }
+
+        let unblock_timestamp = match values[8].as_int() {
+            0 => None,
+            timestamp => Some(
+                u32::try_from(timestamp).map_err(|_| CodecError::TimestampOutOfRange(timestamp))?,
+            ),
+        };
+
+        Ok(Self {
+            output_serial_number: Word::new([values[0], values[1], values[2], values[3]]),
+            dest_chain: values[4].as_int(),
+            dest_address: decode_address(&values[5..8]),
+            unblock_timestamp,
+            calldata,
+            call_address,
+        })
+    }
+}
+
+impl TryFrom<&NoteInputs> for CrosschainInputs {
+    type Error = CodecError;
+
+    fn try_from(inputs: &NoteInputs) -> Result<Self, Self::Error> {
+        Self::try_from(inputs.values())
+    }
+}
+
+/// Inputs of the BRIDGE note carrying the exit event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeEventInputs {
+    /// Burned wrapped asset
+    pub asset: FungibleAsset,
+    pub dest_chain: u64,
+    pub dest_address: [Felt; 3],
+    pub calldata: Vec<u8>,
+    /// Contract called with the calldata on the destination chain
+    pub call_address: Option<[Felt; 3]>,
+}
+
+impl BridgeEventInputs {
+    pub fn to_elements(&self) -> Vec<Felt> {
+        let mut elements = Vec::from(Word::from(self.asset).as_elements());
+        elements.push(Felt::new(self.dest_chain));
+        elements.extend(encode_address(self.dest_address));
+        encode_call(&mut elements, &self.calldata, self.call_address);
+        elements
+    }
+
+    pub fn to_note_inputs(&self) -> Result<NoteInputs, NoteError> {
+        NoteInputs::new(self.to_elements())
+    }
+}
+
+impl TryFrom<&[Felt]> for BridgeEventInputs {
+    type Error = CodecError;
+
+    /// Decodes the BRIDGE inputs, ignoring the zero padding the inputs may be followed by.
+    fn try_from(values: &[Felt]) -> Result<Self, Self::Error> {
+        check_inputs_len(values, BRIDGE_MIN_INPUTS)?;
+        let (calldata, call_address, _) = decode_call(values, BRIDGE_CALLDATA_OFFSET)?;
+
+        let asset = match Asset::try_from(Word::new([values[0], values[1], values[2], values[3]]))? {
+            Asset::Fungible(asset) => asset,
+            Asset::NonFungible(_) => return Err(CodecError::NonFungibleAsset),
+        };
+
+        Ok(Self {
+            asset,
+            dest_chain: values[4].as_int(),
+            dest_address: decode_address(&values[5..8]),
+            calldata,
+            call_address,
+        })
+    }
+}
+
+impl TryFrom<&NoteInputs> for BridgeEventInputs {
+    type Error = CodecError;
+
+    fn try_from(inputs: &NoteInputs) -> Result<Self, Self::Error> {
+        Self::try_from(inputs.values())
+    }
+}
+
+// HELPERS
+// ================================================================================================
+
+fn check_inputs_len(values: &[Felt], expected: usize) -> Result<(), CodecError> {
+    if values.len() < expected {
+        return Err(CodecError::InputsLengthMismatch { expected, actual: values.len() });
+    }
+
+    Ok(())
+}
+
+fn encode_address(address: [Felt; 3]) -> [Felt; 3] {
+    [address[2], address[1], address[0]]
+}
+
+fn decode_address(felts: &[Felt]) -> [Felt; 3] {
+    [felts[2], felts[1], felts[0]]
+}
+
+/// Appends `[calldata_bytes_length, calldata.., call_addr(3)]`, a zero address standing for no
+/// call.
+fn encode_call(elements: &mut Vec<Felt>, calldata: &[u8], call_address: Option<[Felt; 3]>) {
+    elements.push(Felt::new(calldata.len() as u64));
+    elements.extend(calldata_to_felts(calldata));
+    elements.extend(encode_address(call_address.unwrap_or([Felt::ZERO; 3])));
+}
+
+/// Decodes the calldata and call address starting at `offset`, returning the index past them.
+fn decode_call(
+    values: &[Felt],
+    offset: usize,
+) -> Result<(Vec<u8>, Option<[Felt; 3]>, usize), CodecError> {
+    let length = values[offset].as_int();
+    let calldata_felts = usize::try_from(length)
+        .map(calldata_felts_len)
+        .unwrap_or(usize::MAX);
+    let call_address_offset = (offset + 1).saturating_add(calldata_felts);
+    let end = call_address_offset.saturating_add(3);
+    check_inputs_len(values, end)?;
+
+    let calldata = felts_to_calldata(length as usize, &values[offset + 1..call_address_offset])?;
+    let call_address = decode_address(&values[call_address_offset..end]);
+    let call_address = (call_address != [Felt::ZERO; 3]).then_some(call_address);
+
+    Ok((calldata, call_address, end))
+}
+
+/// Proptest strategies for the note inputs, shared with the integration tests.
+#[cfg(any(feature = "testing", test))]
+pub mod testing {
+    use miden_objects::{Felt, StarkField, Word};
+    use proptest::prelude::*;
+
+    use super::{CrosschainInputs, CROSSCHAIN_MIN_INPUTS};
+
+    /// Calldata filling the CROSSCHAIN note up to the maximum number of note inputs.
+    pub const MAX_CALLDATA_LEN: usize = (128 - CROSSCHAIN_MIN_INPUTS) * 4;
+
+    pub fn felt() -> impl Strategy<Value = Felt> {
+        (0..Felt::MODULUS).prop_map(Felt::new)
+    }
+
+    pub fn address() -> impl Strategy<Value = [Felt; 3]> {
+        [felt(), felt(), felt()]
+    }
+
+    prop_compose! {
+        pub fn crosschain_inputs()(
+            serial in [felt(), felt(), felt(), felt()],
+            dest_chain in 0..Felt::MODULUS,
+            dest_address in address(),
+            unblock_timestamp in prop::option::of(1..=u32::MAX),
+            calldata in prop::collection::vec(any::<u8>(), 0..=MAX_CALLDATA_LEN),
+            call_address in prop::option::of(address()),
+        ) -> CrosschainInputs {
+            CrosschainInputs {
+                output_serial_number: Word::new(serial),
+                dest_chain,
+                dest_address,
+                unblock_timestamp,
+                calldata,
+                call_address: call_address.filter(|address| *address != [Felt::new(0); 3]),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{Felt, Word};
+    use proptest::prelude::*;
+
+    use super::{testing::crosschain_inputs, CodecError, CrosschainInputs, CROSSCHAIN_MIN_INPUTS};
+
+    proptest! {
+        #[test]
+        fn should_decode_encoded_crosschain_inputs(inputs in crosschain_inputs()) {
+            let note_inputs = inputs.to_note_inputs().unwrap();
+
+            prop_assert_eq!(note_inputs.values().len(), CROSSCHAIN_MIN_INPUTS + inputs.calldata.len().div_ceil(4));
+            prop_assert_eq!(CrosschainInputs::try_from(&note_inputs).unwrap(), inputs);
+        }
+    }
+
+    #[test]
+    fn should_reject_trailing_crosschain_inputs() {
+        let inputs = CrosschainInputs {
+            output_serial_number: Word::new([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]),
+            dest_chain: 11155111,
+            dest_address: [Felt::new(1), Felt::new(2), Felt::new(3)],
+            unblock_timestamp: None,
+            calldata: vec![1, 2, 3, 4, 5],
+            call_address: None,
+        };
+
+        let mut elements = inputs.to_elements();
+        assert!(matches!(
+            CrosschainInputs::try_from(&elements[..elements.len() - 1]),
+            Err(CodecError::InputsLengthMismatch { expected: 15, actual: 14 })
+        ));
+
+        elements.push(Felt::new(0));
+        assert!(matches!(
+            CrosschainInputs::try_from(elements.as_slice()),
+            Err(CodecError::InputsLengthMismatch { expected: 15, actual: 16 })
+        ));
+    }
+}
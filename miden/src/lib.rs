@@ -4,6 +4,7 @@
 extern crate alloc;
 
 pub mod accounts;
+pub mod codec;
 #[cfg(any(feature = "testing", test))]
 pub mod errors;
 pub mod notes;
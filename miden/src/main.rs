@@ -1,22 +1,18 @@
 use core::fmt;
-use std::{env, fs::File, io::Write, num::ParseIntError};
+use std::num::ParseIntError;
 
-use miden_lib::note::utils::build_p2id_recipient;
-use miden_objects::{
-    account::AccountId,
-    asset::{Asset, FungibleAsset},
-    crypto::{
-        rand::{FeltRng, RpoRandomCoin},
-        utils::word_to_hex,
-    },
-    note::{
-        Note, NoteAssets, NoteDetails, NoteExecutionHint, NoteFile, NoteMetadata, NoteTag, NoteType,
-    },
-    utils::{parse_hex_string_as_word, Serializable},
-    AccountIdError, AssetError, Felt, FieldElement, NoteError, Word,
-};
+use clap::{Parser, Subcommand};
+use miden_bridge::utils::AddressFormatError;
+use miden_objects::{utils::DeserializationError, AccountIdError, AssetError, NoteError};
 use thiserror::Error;
 
+use crate::cli::{
+    address::DecodeAddressCmd, crosschain::CrosschainCmd, generate::GenerateCmd,
+    inspect::InspectNoteCmd, reconstruct::ReconstructCmd,
+};
+
+mod cli;
+
 #[derive(Error, Debug)]
 pub enum CliError {
     #[error(transparent)]
@@ -33,111 +29,54 @@ pub enum CliError {
     InvalidAmountError(#[from] ParseIntError),
     #[error(transparent)]
     FungibleAssetBuildError(#[from] AssetError),
-    #[error("Unknown command: `{0}`")]
-    UnknownCommandError(String),
-    #[error("Invalid note save path")]
-    InvalidSavePathError(),
     #[error(transparent)]
     IOError(#[from] std::io::Error),
+    #[error("Failed to read OS entropy: {0}")]
+    EntropyError(String),
+    #[error("Serial number `{0}` was already emitted")]
+    SerialNumberReuseError(String),
+    #[error(transparent)]
+    NoteFileDecodingError(#[from] DeserializationError),
+    #[error(transparent)]
+    AddressFormatError(#[from] AddressFormatError),
+    #[error(transparent)]
+    JsonEncodingError(#[from] serde_json::Error),
 }
 
-pub fn main() -> Result<(), CliError> {
-    let args: Vec<String> = env::args().collect();
-
-    let cmd = &args[1];
+/// Builds and inspects the notes used to bridge assets between EVM chains and Miden.
+#[derive(Parser, Debug)]
+#[command(name = "miden-bridge", version, about)]
+pub struct Cli {
+    /// Print the command output as JSON
+    #[arg(long, global = true)]
+    json: bool,
 
-    if cmd == "generate" {
-        build_recipient(args[2].clone())
-    } else if cmd == "reconstruct" {
-        restore_note(args[2].clone(), args[3].clone(), args[4].clone(), args[5].clone())
-    } else {
-        Err(CliError::UnknownCommandError(cmd.clone()))
-    }
+    #[command(subcommand)]
+    command: Command,
 }
 
-fn build_recipient(receiver: String) -> Result<(), CliError> {
-    let mut rng = RpoRandomCoin::new(Word::new(
-        [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]
-    ));
-
-    let serial_number: Word = rng.draw_word();
-    let receiver =
-        AccountId::from_hex(&receiver.as_str()).map_err(|e| CliError::ParseAccountIdError(e))?;
-
-    let recipient = build_p2id_recipient(receiver, serial_number.clone())
-        .map_err(|e| CliError::BuildNoteRecipientError(e))?;
-
-    let recipient_digest = recipient.digest().to_hex();
-    let serial_number =
-        word_to_hex(&serial_number).map_err(|e| CliError::WordHexEncodingError(e))?;
-
-    println!("Recipient: {recipient_digest}");
-    println!("Serial number: {serial_number}");
-
-    Ok(())
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generate a P2ID recipient for a deposit from an EVM chain
+    Generate(GenerateCmd),
+    /// Reconstruct the P2ID note minted by the relayer into a `.mno` file
+    Reconstruct(ReconstructCmd),
+    /// Build a CROSSCHAIN note bridging an asset from Miden to an EVM chain
+    Crosschain(CrosschainCmd),
+    /// Print the content of a `.mno` note file
+    InspectNote(InspectNoteCmd),
+    /// Convert an EVM address to the note input felts and back
+    DecodeAddress(DecodeAddressCmd),
 }
 
-fn restore_note(
-    receiver: String,
-    serial_num_hex: String,
-    bridged_amount: String,
-    faucet_id: String,
-) -> Result<(), CliError> {
-    let serial_number = parse_hex_string_as_word(&serial_num_hex)
-        .map_err(|e| CliError::BytesHexDecodingError(e.parse().unwrap()))?;
-
-    let receiver =
-        AccountId::from_hex(&receiver.as_str()).map_err(|e| CliError::ParseAccountIdError(e))?;
-
-    let recipient = build_p2id_recipient(receiver, Word::new(serial_number.clone()))
-        .map_err(|e| CliError::BuildNoteRecipientError(e))?;
-
-    let faucet_id =
-        AccountId::from_hex(&faucet_id.as_str()).map_err(|e| CliError::ParseAccountIdError(e))?;
-
-    let bridged_amount = u64::from_str_radix(bridged_amount.as_str(), 10)
-        .map_err(|e| CliError::InvalidAmountError(e))?;
-
-    let asset = FungibleAsset::new(faucet_id, bridged_amount)
-        .map_err(|e| CliError::FungibleAssetBuildError(e))?;
-
-    let note = Note::new(
-        NoteAssets::new(vec![Asset::from(asset)])
-            .map_err(|e| CliError::BuildExportableNoteError(e))?,
-        NoteMetadata::new(
-            faucet_id,
-            NoteType::Private,
-            NoteTag::for_local_use_case(1, 0).map_err(|e| CliError::BuildExportableNoteError(e))?,
-            NoteExecutionHint::Always,
-            Felt::ZERO,
-        )
-        .map_err(|e| CliError::BuildExportableNoteError(e))?,
-        recipient,
-    );
-
-    let note_id = note.clone().id().to_hex();
-
-    println!("Reconstructed note id: {note_id}");
-
-    let note_details = NoteDetails::new(note.assets().clone(), note.recipient().clone());
-
-    const BRIDGE_USECASE: u16 = 14594;
-
-    let note_text = NoteFile::NoteDetails {
-        details: note_details,
-        after_block_num: 0.into(),
-        tag: Some(
-            NoteTag::for_local_use_case(BRIDGE_USECASE, 0)
-                .map_err(|e| CliError::BuildExportableNoteError(e))?,
-        ),
-    };
-
-    let file_path = env::current_dir()
-        .map_err(|_| CliError::InvalidSavePathError())?
-        .join("reconstructed_note.mno");
-
-    let mut file = File::create(file_path).map_err(CliError::from)?;
-    file.write_all(&note_text.to_bytes()).map_err(CliError::from)?;
-
-    Ok(())
+pub fn main() -> Result<(), CliError> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Generate(cmd) => cmd.execute(cli.json),
+        Command::Reconstruct(cmd) => cmd.execute(cli.json),
+        Command::Crosschain(cmd) => cmd.execute(cli.json),
+        Command::InspectNote(cmd) => cmd.execute(cli.json),
+        Command::DecodeAddress(cmd) => cmd.execute(cli.json),
+    }
 }
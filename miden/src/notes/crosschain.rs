@@ -1,21 +1,18 @@
 use miden_objects::{
     account::AccountId,
     asset::FungibleAsset,
-    note::{
-        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteTag,
-        NoteType,
-    },
+    note::{Note, NoteAssets, NoteExecutionHint, NoteMetadata, NoteRecipient, NoteTag, NoteType},
     Felt, FieldElement, NoteError, Word,
 };
 
 use super::bridge::croschain;
+use crate::codec::CrosschainInputs;
 
+/// Builds a CROSSCHAIN note burning `asset_amount` of the wrapped asset and emitting a BRIDGE
+/// note described by `inputs`.
 pub fn new_crosschain_note(
     serial_number: Word,
-    output_serial_number: Word,
-    dest_chain: Felt,
-    dest_addr: [Felt; 3],
-    unblock_timestamp: Option<u32>,
+    inputs: &CrosschainInputs,
     faucet_id: AccountId,
     asset_amount: u64,
     sender: AccountId,
@@ -32,25 +29,7 @@ pub fn new_crosschain_note(
             NoteExecutionHint::always(),
             Felt::ZERO,
         )?,
-        NoteRecipient::new(
-            serial_number,
-            croschain(),
-            NoteInputs::new(vec![
-                output_serial_number[3],
-                output_serial_number[2],
-                output_serial_number[1],
-                output_serial_number[0],
-                dest_chain,
-                dest_addr[2],
-                dest_addr[1],
-                dest_addr[0],
-                Felt::new(unblock_timestamp.unwrap_or(0) as u64),
-                Felt::ZERO,
-                Felt::ZERO,
-                Felt::ZERO,
-                Felt::ZERO,
-            ])?,
-        ),
+        NoteRecipient::new(serial_number, croschain(), inputs.to_note_inputs()?),
     );
 
     Ok(note)
@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use core::fmt;
 
 use alloy_primitives::{
@@ -20,6 +21,61 @@ pub enum AddressFormatError {
     FmtError(#[from] fmt::Error),
 }
 
+/// Number of calldata bytes packed into a single note input felt.
+pub const CALLDATA_BYTES_PER_FELT: usize = 4;
+
+#[derive(Error, Debug)]
+pub enum CalldataFormatError {
+    #[error("calldata of {length} bytes needs {expected} felts, got {actual}")]
+    MissingCalldataFelts { length: usize, expected: usize, actual: usize },
+    #[error("calldata felt {0} does not fit into {CALLDATA_BYTES_PER_FELT} bytes")]
+    MalformedCalldataFelt(u64),
+}
+
+/// Returns the number of felts used to encode `length` calldata bytes.
+pub fn calldata_felts_len(length: usize) -> usize {
+    length.div_ceil(CALLDATA_BYTES_PER_FELT)
+}
+
+/// Packs calldata into felts of [CALLDATA_BYTES_PER_FELT] big-endian bytes each, the last felt
+/// being right-padded with zeros.
+///
+/// The byte length has to be stored alongside the felts to drop the padding on decoding.
+pub fn calldata_to_felts(calldata: &[u8]) -> Vec<Felt> {
+    calldata
+        .chunks(CALLDATA_BYTES_PER_FELT)
+        .map(|chunk| {
+            let mut bytes = [0u8; CALLDATA_BYTES_PER_FELT];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            Felt::new(u32::from_be_bytes(bytes) as u64)
+        })
+        .collect()
+}
+
+/// Unpacks `length` calldata bytes from felts produced by [calldata_to_felts].
+///
+/// Felts past the calldata are ignored.
+pub fn felts_to_calldata(length: usize, felts: &[Felt]) -> Result<Vec<u8>, CalldataFormatError> {
+    let expected = calldata_felts_len(length);
+    if felts.len() < expected {
+        return Err(CalldataFormatError::MissingCalldataFelts {
+            length,
+            expected,
+            actual: felts.len(),
+        });
+    }
+
+    let mut calldata = Vec::with_capacity(expected * CALLDATA_BYTES_PER_FELT);
+    for felt in &felts[..expected] {
+        let value = u32::try_from(felt.as_int())
+            .map_err(|_| CalldataFormatError::MalformedCalldataFelt(felt.as_int()))?;
+        calldata.extend_from_slice(&value.to_be_bytes());
+    }
+    calldata.truncate(length);
+
+    Ok(calldata)
+}
+
 pub fn evm_address_to_felts<T>(address: T) -> Result<[Felt; 3], AddressFormatError>
 where
     T: AsRef<str>,
@@ -49,11 +105,15 @@ pub fn felts_to_evm_addresses(felts: [Felt; 3]) -> Result<Address, AddressFormat
 
 #[cfg(test)]
 mod tests {
-    use alloc::string::ToString;
+    use alloc::{string::ToString, vec::Vec};
 
-    use miden_objects::utils::ToHex;
+    use alloy_primitives::hex::FromHex;
+    use miden_objects::{utils::ToHex, Felt};
 
-    use super::{evm_address_to_felts, felts_to_evm_addresses};
+    use super::{
+        calldata_to_felts, evm_address_to_felts, felts_to_calldata, felts_to_evm_addresses,
+        CalldataFormatError,
+    };
 
     #[test]
     fn should_decode_encoded_evm_address() {
@@ -71,4 +131,38 @@ mod tests {
             assert_eq!(input.to_lowercase(), hex_output.to_lowercase().as_str());
         }
     }
+
+    #[test]
+    fn should_decode_encoded_calldata() {
+        // bridgeAndCall style payload: transfer(address,uint256) on the destination chain
+        let transfer = Vec::from_hex(
+            "a9059cbb\
+             000000000000000000000000ab348cb6a2bf1ae152c793e091ff0545cf0ad7b7\
+             00000000000000000000000000000000000000000000000000000000000003e8",
+        )
+        .unwrap();
+
+        for calldata in [Vec::new(), vec![0xff], vec![1, 2, 3, 4, 5], transfer] {
+            let felts = calldata_to_felts(&calldata);
+            assert_eq!(felts.len(), calldata.len().div_ceil(4));
+
+            let mut padded = felts.clone();
+            padded.extend([Felt::new(0); 3]);
+            assert_eq!(felts_to_calldata(calldata.len(), &padded).unwrap(), calldata);
+        }
+    }
+
+    #[test]
+    fn should_reject_truncated_calldata() {
+        let felts = calldata_to_felts(&[1, 2, 3, 4, 5]);
+
+        assert!(matches!(
+            felts_to_calldata(5, &felts[..1]),
+            Err(CalldataFormatError::MissingCalldataFelts { expected: 2, actual: 1, .. })
+        ));
+        assert!(matches!(
+            felts_to_calldata(4, &[Felt::new(u32::MAX as u64 + 1)]),
+            Err(CalldataFormatError::MalformedCalldataFelt(_))
+        ));
+    }
 }
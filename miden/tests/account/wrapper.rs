@@ -1,49 +1,140 @@
 use miden_bridge::{
     accounts::{testing::create_token_wrapper_account_builder, token_wrapper::bridge_note_tag},
-    notes::bridge::{bridge, croschain},
-};
-use miden_lib::{
-    account::{auth::{AuthRpoFalcon512Acl, AuthRpoFalcon512AclConfig}, faucets::BasicFungibleFaucet},
-    transaction::TransactionKernel,
+    codec::{testing::crosschain_inputs, BridgeEventInputs, CrosschainInputs},
+    notes::{
+        bridge::{bridge, croschain},
+        crosschain::new_crosschain_note,
+    },
+    utils::evm_address_to_felts,
 };
-use miden_lib::utils::ScriptBuilder;
+use miden_lib::{account::faucets::BasicFungibleFaucet, utils::ScriptBuilder};
 use miden_objects::{
-    account::{AccountId, AccountStorageMode, AuthSecretKey},
+    account::{Account, AccountId, AccountStorageMode},
     asset::{FungibleAsset, TokenSymbol},
-    crypto::{
-        dsa::rpo_falcon512::{PublicKey, SecretKey},
-        rand::{FeltRng, RpoRandomCoin},
-    },
+    crypto::rand::{FeltRng, RpoRandomCoin},
     note::{
-        Note, NoteAssets, NoteExecutionHint, NoteId, NoteInputs, NoteMetadata, NoteRecipient,
-        NoteTag, NoteType,
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteTag,
+        NoteType,
     },
     testing::account_id::ACCOUNT_ID_SENDER,
-    transaction::{OutputNote, TransactionScript},
+    transaction::OutputNote,
     Felt, FieldElement, Word,
 };
 use miden_testing::{AccountState, Auth, MockChain};
-use miden_bridge::errors::note_errors::ERR_CROSSCHAIN_TOO_EARLY_EXECUTION;
+use miden_tx::TransactionExecutorError;
+use proptest::prelude::*;
+use miden_bridge::errors::note_errors::{
+    ERR_CROSSCHAIN_CALLDATA_LENGTH_MISMATCH, ERR_CROSSCHAIN_TOO_EARLY_EXECUTION,
+};
 use crate::assert_transaction_executor_error;
 
-pub fn get_new_pk_and_authenticator(seed: [Felt; 4]) -> (PublicKey, AuthSecretKey) {
-    let seed = Word::from(seed);
-    let mut rng = RpoRandomCoin::new(seed);
-    let sec_key = SecretKey::with_rng(&mut rng);
-    (sec_key.public_key(), AuthSecretKey::RpoFalcon512(sec_key))
-}
-
 const DAY: u32 = 60 * 60 * 24;
 
 #[test]
 fn should_issue_public_bridge_note() -> anyhow::Result<()> {
-    let mut mock_chain_builder = MockChain::builder();
+    let (mut mock_chain, wrapper) = deploy_wrapper([1; 32])?;
+
+    let mut rng = RpoRandomCoin::new(Word::from([
+        Felt::new(456),
+        Felt::new(456),
+        Felt::new(456),
+        Felt::new(456),
+    ]));
+
+    let unlock_timestamp = mock_chain.latest_block_header().timestamp() + 7 * DAY;
+
+    let inputs = CrosschainInputs {
+        output_serial_number: rng.draw_word(),
+        dest_chain: 123,
+        dest_address: [rng.draw_element(), rng.draw_element(), rng.draw_element()],
+        unblock_timestamp: Some(unlock_timestamp),
+        calldata: Vec::new(),
+        call_address: None,
+    };
+
+    let note = crosschain_note(&wrapper, &inputs, 1000)?;
+
+    mock_chain.add_pending_note(OutputNote::Full(note.clone()));
+    mock_chain.prove_next_block().expect("Unable to prove next block");
+
+    assert_transaction_executor_error!(
+        execute_bridge(&mock_chain, &wrapper, &note, &inputs),
+        ERR_CROSSCHAIN_TOO_EARLY_EXECUTION
+    );
+
+    mock_chain.prove_next_block_at(unlock_timestamp + DAY)
+        .expect("Unable to generate next block");
+
+    let (_, bridge_note) = execute_bridge(&mock_chain, &wrapper, &note, &inputs)
+        .expect("Unable to execute crosschain consume transaction");
 
-    let (pub_key, _secret_key) =
-        get_new_pk_and_authenticator([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]);
+    assert_eq!(bridge_note.metadata().tag(), bridge_note_tag());
+    assert_eq!(bridge_note.metadata().note_type(), NoteType::Public);
+
+    Ok(())
+}
+
+#[test]
+fn should_forward_calldata_to_bridge_note() -> anyhow::Result<()> {
+    let (mut mock_chain, wrapper) = deploy_wrapper([2; 32])?;
+
+    // bridgeAndCall style exit: transfer(address,uint256) executed on the destination chain
+    let calldata = hex::decode(
+        "a9059cbb\
+         000000000000000000000000ab348cb6a2bf1ae152c793e091ff0545cf0ad7b7\
+         00000000000000000000000000000000000000000000000000000000000003e8",
+    )?;
+
+    let inputs = CrosschainInputs {
+        output_serial_number: Word::new([Felt::new(789), Felt::ZERO, Felt::ZERO, Felt::ZERO]),
+        dest_chain: 123,
+        dest_address: evm_address_to_felts("0x20b0bad0c3C9C3f40A88801A5E8e24043B9c6C10")?,
+        unblock_timestamp: None,
+        calldata: calldata.clone(),
+        call_address: Some(evm_address_to_felts("0xA69FD3dB73147241E129EAd5B8F06C4F89E43D37")?),
+    };
+
+    let note = crosschain_note(&wrapper, &inputs, 1000)?;
+
+    // calldata_bytes_length claims more felts than the note carries
+    let mut malformed_inputs = note.recipient().inputs().values().to_vec();
+    malformed_inputs[9] = Felt::new(calldata.len() as u64 + 4);
+    let malformed_note = Note::new(
+        note.assets().clone(),
+        note.metadata().clone(),
+        NoteRecipient::new(
+            Word::new([Felt::new(2), Felt::ZERO, Felt::ZERO, Felt::ZERO]),
+            croschain(),
+            NoteInputs::new(malformed_inputs)?,
+        ),
+    );
+
+    mock_chain.add_pending_note(OutputNote::Full(note.clone()));
+    mock_chain.add_pending_note(OutputNote::Full(malformed_note.clone()));
+    mock_chain.prove_next_block()?;
+
+    assert_transaction_executor_error!(
+        execute_bridge(&mock_chain, &wrapper, &malformed_note, &inputs),
+        ERR_CROSSCHAIN_CALLDATA_LENGTH_MISMATCH
+    );
+
+    let (asset, bridge_note) = execute_bridge(&mock_chain, &wrapper, &note, &inputs)
+        .expect("Unable to execute crosschain consume transaction");
+
+    let bridge_inputs = BridgeEventInputs::try_from(bridge_note.recipient().inputs())?;
+    assert_eq!(bridge_inputs.calldata, calldata);
+    assert_eq!(bridge_inputs, inputs.bridge_event_inputs(asset));
+
+    Ok(())
+}
+
+/// Deploys a wrapper faucet on a new mock chain and mints some wrapped tokens with it, so that
+/// CROSSCHAIN notes can burn them.
+fn deploy_wrapper(seed: [u8; 32]) -> anyhow::Result<(MockChain, Account)> {
+    let mut mock_chain_builder = MockChain::builder();
 
     let wrapper_builder = create_token_wrapper_account_builder(
-        [1; 32],
+        seed,
         TokenSymbol::new("TEST")?,
         6,
         Felt::new(1000000),
@@ -52,83 +143,18 @@ fn should_issue_public_bridge_note() -> anyhow::Result<()> {
         AccountStorageMode::Public,
     )?;
 
-    wrapper_builder
-        .clone()
-        .with_auth_component(AuthRpoFalcon512Acl::new(
-            pub_key,
-            AuthRpoFalcon512AclConfig::new().with_auth_trigger_procedures(
-                vec![BasicFungibleFaucet::distribute_digest()]
-            ),
-        )?)
-        .build()?;
-
     let mut wrapper = mock_chain_builder.add_account_from_builder(
         Auth::Acl {
             auth_trigger_procedures: vec![BasicFungibleFaucet::distribute_digest()],
             allow_unauthorized_input_notes: true,
             allow_unauthorized_output_notes: true
         },
-        wrapper_builder.clone(),
+        wrapper_builder,
         AccountState::Exists,
     )?;
 
     let mut mock_chain = mock_chain_builder.build()?;
 
-    let fungible_asset = FungibleAsset::new(wrapper.id(), 1000).unwrap().into();
-
-    let mut rng = RpoRandomCoin::new(Word::from([
-        Felt::new(456),
-        Felt::new(456),
-        Felt::new(456),
-        Felt::new(456),
-    ]));
-
-    let output_serial_num = rng.draw_word();
-
-    let receiver_address = [rng.draw_element(), rng.draw_element(), rng.draw_element()];
-
-    let call_address = [Felt::ZERO, Felt::ZERO, Felt::ZERO];
-
-    let chain_id: u64 = 123;
-    let unlock_timestamp = mock_chain.latest_block_header().timestamp() + 7 * DAY;
-
-    let note_inputs = NoteInputs::new(vec![
-        output_serial_num[0],
-        output_serial_num[1],
-        output_serial_num[2],
-        output_serial_num[3],
-        Felt::new(chain_id),
-        receiver_address[0],
-        receiver_address[1],
-        receiver_address[2],
-        Felt::new(unlock_timestamp as u64),
-        Felt::ZERO,
-        call_address[0],
-        call_address[1],
-        call_address[2],
-    ])?;
-
-    let note = Note::new(
-        NoteAssets::new(vec![fungible_asset])?,
-        NoteMetadata::new(
-            AccountId::try_from(ACCOUNT_ID_SENDER)?,
-            NoteType::Public,
-            NoteTag::from_account_id(wrapper.id()),
-            NoteExecutionHint::Always,
-            Felt::ZERO,
-        )?,
-        NoteRecipient::new(
-            Word::new([Felt::new(1), Felt::ZERO, Felt::ZERO, Felt::ZERO]),
-            croschain(),
-            note_inputs,
-        ),
-    );
-
-    mock_chain.add_pending_note(OutputNote::Full(note.clone()));
-    mock_chain.prove_next_block().expect("Unable to prove next block");
-
-    let mint_tx_inputs = mock_chain.get_transaction_inputs(wrapper.clone(), None, &[], &[])?;
-
     let mint_tx_script_code = format!(
         "
             begin
@@ -151,114 +177,131 @@ fn should_issue_public_bridge_note() -> anyhow::Result<()> {
             end
             ",
         note_type = Felt::from(NoteType::Private),
-        recipient = rng.draw_word(),
+        recipient = Word::new([Felt::new(789), Felt::ZERO, Felt::ZERO, Felt::ZERO]),
         aux = 0,
         tag = Felt::from(NoteTag::for_local_use_case(0, 0)?),
         note_execution_hint = Felt::from(NoteExecutionHint::Always),
         amount = 10000
     );
 
-    let mint_tx_script =
-        ScriptBuilder::default().compile_tx_script(mint_tx_script_code)?;
+    let mint_tx_inputs = mock_chain.get_transaction_inputs(wrapper.clone(), None, &[], &[])?;
 
-    let executed_mint_transaction = mock_chain.build_tx_context(
-            wrapper.clone(),
-            &[],
-            &[]
-        )?
-            .tx_script(mint_tx_script)
-            .tx_inputs(mint_tx_inputs)
-            .build()?
-            .execute_blocking().expect("Unable to execute mint tx");
+    let executed_mint_transaction = mock_chain.build_tx_context(wrapper.clone(), &[], &[])?
+        .tx_script(ScriptBuilder::default().compile_tx_script(mint_tx_script_code)?)
+        .tx_inputs(mint_tx_inputs)
+        .build()?
+        .execute_blocking()?;
 
     mock_chain.add_pending_executed_transaction(&executed_mint_transaction.clone())?;
     mock_chain.prove_next_block()?;
 
     wrapper.apply_delta(&executed_mint_transaction.account_delta().clone())?;
 
-    let asset_word = Word::from(fungible_asset);
-
-    let expected_recipient = NoteRecipient::new(
-        output_serial_num,
-        bridge(),
-        NoteInputs::new(vec![
-            asset_word[0],
-            asset_word[1],
-            asset_word[2],
-            asset_word[3],
-            Felt::new(chain_id),
-            receiver_address[0],
-            receiver_address[1],
-            receiver_address[2],
-            Felt::ZERO,
-            call_address[0],
-            call_address[1],
-            call_address[2],
-        ])?,
-    );
+    Ok((mock_chain, wrapper))
+}
+
+/// Builds the public CROSSCHAIN note burning `amount` tokens of the wrapper with `inputs`.
+fn crosschain_note(
+    wrapper: &Account,
+    inputs: &CrosschainInputs,
+    amount: u64,
+) -> anyhow::Result<Note> {
+    let sender = AccountId::try_from(ACCOUNT_ID_SENDER)?;
+    let crosschain_note = new_crosschain_note(
+        Word::new([Felt::new(1), Felt::ZERO, Felt::ZERO, Felt::ZERO]),
+        inputs,
+        wrapper.id(),
+        amount,
+        sender,
+        NoteTag::from_account_id(wrapper.id()),
+    )?;
 
-    let expected_note_id =
-        NoteId::new(expected_recipient.digest(), NoteAssets::new(vec![])?.commitment());
+    Ok(Note::new(
+        crosschain_note.assets().clone(),
+        NoteMetadata::new(
+            sender,
+            NoteType::Public,
+            crosschain_note.metadata().tag(),
+            NoteExecutionHint::Always,
+            Felt::ZERO,
+        )?,
+        crosschain_note.recipient().clone(),
+    ))
+}
 
+/// Consumes the CROSSCHAIN `note` with the wrapper, returning the burned asset and the BRIDGE
+/// note emitted by the wrapper `bridge` procedure, which must match the codec layout of `inputs`.
+fn execute_bridge(
+    mock_chain: &MockChain,
+    wrapper: &Account,
+    note: &Note,
+    inputs: &CrosschainInputs,
+) -> Result<(FungibleAsset, Note), TransactionExecutorError> {
+    let asset =
+        note.assets().iter().next().expect("CROSSCHAIN note without asset").unwrap_fungible();
+
+    // The kernel reads the inputs of the emitted note from the advice provider by the recipient
+    // digest the procedure builds, so a layout the codec disagrees with fails the transaction
     let expected_note = Note::new(
-        NoteAssets::new(vec![])?,
+        NoteAssets::new(vec![]).expect("empty note assets"),
         NoteMetadata::new(
             wrapper.id(),
             NoteType::Public,
             bridge_note_tag(),
             NoteExecutionHint::Always,
             Felt::ZERO,
-        )?,
-        expected_recipient.clone(),
-    );
-
-    let tx_inputs =
-        mock_chain.get_transaction_inputs(wrapper.clone(), None, &[note.clone().id()], &[])?;
-
-
-    let failed_executed_transaction = mock_chain.build_tx_context(
-            wrapper.clone(),
-            &[],
-            &[]
-        )?
-            .tx_inputs(tx_inputs)
-            .extend_expected_output_notes(vec![OutputNote::Full(expected_note.clone())])
-            .build()?
-            .execute_blocking();
-
-    assert_transaction_executor_error!(
-        failed_executed_transaction,
-        ERR_CROSSCHAIN_TOO_EARLY_EXECUTION
+        )
+        .expect("valid BRIDGE note metadata"),
+        NoteRecipient::new(
+            inputs.output_serial_number,
+            bridge(),
+            inputs.bridge_event_inputs(asset).to_note_inputs().expect("valid BRIDGE note inputs"),
+        ),
     );
 
-    mock_chain.prove_next_block_at(unlock_timestamp + DAY)
-        .expect("Unable to generate next block");
-
+    let tx_inputs = mock_chain
+        .get_transaction_inputs(wrapper.clone(), None, &[note.id()], &[])
+        .expect("Unable to get transaction inputs");
 
-    let tx_inputs = mock_chain.
-        get_transaction_inputs(
-            wrapper.clone(),
-            None,
-            &[note.clone().id()],
-            &[]
-        )?;
-
-    let executed_transaction = mock_chain.build_tx_context(
-        wrapper.clone(),
-        &[],
-        &[]
-    )?
-        .tx_inputs(tx_inputs.clone())
+    let executed_transaction = mock_chain.build_tx_context(wrapper.clone(), &[], &[])
+        .expect("Unable to build transaction context")
+        .tx_inputs(tx_inputs)
         .extend_expected_output_notes(vec![OutputNote::Full(expected_note.clone())])
-        .build()?
-        .execute_blocking().expect("Unable to execute crosschain consume transaction");
+        .build()
+        .expect("Unable to build transaction context")
+        .execute_blocking()?;
 
     assert_eq!(executed_transaction.output_notes().num_notes(), 1);
-    assert_eq!(
-        executed_transaction.output_notes().get_note(0).metadata().tag(),
-        bridge_note_tag()
-    );
-    assert_eq!(executed_transaction.output_notes().get_note(0).id(), expected_note_id);
+    let output_note = executed_transaction.output_notes().get_note(0);
+    assert_eq!(output_note.id(), expected_note.id());
+    let OutputNote::Full(bridge_note) = output_note else {
+        panic!("BRIDGE note {} is not public", output_note.id());
+    };
 
-    Ok(())
+    Ok((asset, bridge_note.clone()))
+}
+
+proptest! {
+    // every case deploys a wrapper and proves its transactions on a new mock chain
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn should_match_bridge_procedure_layout(inputs in crosschain_inputs(), amount in 1..=10000u64) {
+        // the mock chain is not advanced past any unblock timestamp
+        let inputs = CrosschainInputs { unblock_timestamp: None, ..inputs };
+
+        let (mut mock_chain, wrapper) = deploy_wrapper([3; 32]).unwrap();
+        let note = crosschain_note(&wrapper, &inputs, amount).unwrap();
+        mock_chain.add_pending_note(OutputNote::Full(note.clone()));
+        mock_chain.prove_next_block().unwrap();
+
+        let (asset, bridge_note) = execute_bridge(&mock_chain, &wrapper, &note, &inputs).unwrap();
+
+        prop_assert_eq!(bridge_note.metadata().sender(), asset.faucet_id());
+        prop_assert_eq!(bridge_note.recipient().serial_num(), inputs.output_serial_number);
+        prop_assert_eq!(
+            BridgeEventInputs::try_from(bridge_note.recipient().inputs()).unwrap(),
+            inputs.bridge_event_inputs(asset)
+        );
+    }
 }
@@ -0,0 +1,13 @@
+import { MigrationInterface, QueryRunner } from 'typeorm';
+
+export class ExitDepositCount1760781600000 implements MigrationInterface {
+  name = 'ExitDepositCount1760781600000';
+
+  public async up(queryRunner: QueryRunner): Promise<void> {
+    await queryRunner.query(`ALTER TABLE "exits" ADD "deposit_count" bigint`);
+  }
+
+  public async down(queryRunner: QueryRunner): Promise<void> {
+    await queryRunner.query(`ALTER TABLE "exits" DROP COLUMN "deposit_count"`);
+  }
+}
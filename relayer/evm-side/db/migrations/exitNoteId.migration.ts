@@ -0,0 +1,17 @@
+import { MigrationInterface, QueryRunner } from 'typeorm';
+
+export class ExitNoteId1760868000000 implements MigrationInterface {
+  name = 'ExitNoteId1760868000000';
+
+  public async up(queryRunner: QueryRunner): Promise<void> {
+    await queryRunner.query(`ALTER TABLE "exits" ADD "note_id" text`);
+    await queryRunner.query(
+      `CREATE UNIQUE INDEX "exits_note_id_idx" ON "exits" ("note_id")`,
+    );
+  }
+
+  public async down(queryRunner: QueryRunner): Promise<void> {
+    await queryRunner.query(`DROP INDEX "exits_note_id_idx"`);
+    await queryRunner.query(`ALTER TABLE "exits" DROP COLUMN "note_id"`);
+  }
+}
@@ -10,7 +10,19 @@ export interface AssetOrigin {
   address: string;
 }
 
+export interface MidenApiKey {
+  id: string;
+  secret: string;
+}
+
+export interface DepositId {
+  originNetwork: number;
+  txHash: string;
+  depositCount: number;
+}
+
 export interface SendRequest {
+  deposit: DepositId;
   asset: AssetMetadata & AssetOrigin;
   recipient: string;
   amount: BigNumber;
@@ -1,26 +1,62 @@
 import { Injectable, Logger } from '@nestjs/common';
 import type { paths, components } from 'api';
-import { createPathBasedClient, PathBasedClient } from 'openapi-fetch';
-import { SendRequest, SendResponse } from '../interfaces';
+import { createHmac, randomUUID } from 'crypto';
+import createClient, {
+  Middleware,
+  PathBasedClient,
+  wrapAsPathBasedClient,
+} from 'openapi-fetch';
+import { MidenApiKey, SendRequest, SendResponse } from '../interfaces';
+
+// Signs every request with the API key, as the miden relayer expects: the HMAC-SHA256 of
+// "{timestamp}\n{nonce}\n{METHOD}\n{uri}\n{body}" keyed by the key secret
+function signingMiddleware({ id, secret }: MidenApiKey): Middleware {
+  return {
+    async onRequest({ request }) {
+      const timestamp = Math.floor(Date.now() / 1000).toString();
+      const nonce = randomUUID();
+      const { pathname, search } = new URL(request.url);
+      const body = await request.clone().text();
+      const signature = createHmac('sha256', secret)
+        .update(`${timestamp}\n${nonce}\n${request.method}\n${pathname}${search}\n`)
+        .update(body)
+        .digest('hex');
+
+      request.headers.set('X-Api-Key', id);
+      request.headers.set('X-Timestamp', timestamp);
+      request.headers.set('X-Nonce', nonce);
+      request.headers.set('X-Signature', signature);
+      return request;
+    },
+  };
+}
 
 @Injectable()
 export class MidenApiService {
   private readonly client: PathBasedClient<paths, 'application/json'>;
   private readonly logger = new Logger(MidenApiService.name);
 
-  constructor(apiUrl: string) {
-    this.client = createPathBasedClient<paths, 'application/json'>({
+  constructor(apiUrl: string, apiKey: MidenApiKey) {
+    const client = createClient<paths, 'application/json'>({
       baseUrl: apiUrl,
     });
+    client.use(signingMiddleware(apiKey));
+    this.client = wrapAsPathBasedClient(client);
   }
 
-  async send({ amount, asset, recipient }: SendRequest): Promise<SendResponse> {
+  async send({
+    deposit,
+    amount,
+    asset,
+    recipient,
+  }: SendRequest): Promise<SendResponse> {
     const {
       data: response,
       response: { status },
       error,
     } = await this.client['/mint'].POST({
       body: {
+        deposit,
         asset: {
           assetSymbol: asset.symbol,
           decimals: asset.decimals,
@@ -46,15 +82,16 @@ export class MidenApiService {
     return response;
   }
 
+  // Returns the exits the API key has not acknowledged yet, oldest first
   async pollExits(
-    fromHeight: number,
+    limit: number,
   ): Promise<components['schemas']['PolledEvents']> {
     const {
       data: response,
       response: { status },
       error,
     } = await this.client['/poll'].GET({
-      params: { query: { from: fromHeight } },
+      params: { query: { limit } },
     });
 
     if (status !== 200) {
@@ -70,4 +107,29 @@ export class MidenApiService {
 
     return response;
   }
+
+  async acknowledgeExits(
+    noteIds: Array<string>,
+  ): Promise<components['schemas']['ExitCursor']> {
+    const {
+      data: response,
+      response: { status },
+      error,
+    } = await this.client['/poll/ack'].POST({
+      body: { noteIds },
+    });
+
+    if (status !== 200) {
+      this.logger.warn(`/poll/ack Api responded with status ${status}`);
+      throw new Error(
+        `Miden module responds with error: ${error.code} ${error.message}`,
+      );
+    }
+
+    if (response == null) {
+      throw new Error(`Malformed response from miden module: ${response}`);
+    }
+
+    return response;
+  }
 }
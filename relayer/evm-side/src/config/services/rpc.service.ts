@@ -42,7 +42,10 @@ export class RpcConfigService {
         throw new Error(`Malformed url in env var "${envKey}"`);
       }
 
-      const provider = new MidenApiService(url);
+      const provider = new MidenApiService(url, {
+        id: config.getOrThrow(`MIDEN_API_KEY_ID_CHAIN_${chainId}`),
+        secret: config.getOrThrow(`MIDEN_API_KEY_SECRET_CHAIN_${chainId}`),
+      });
 
       result.set(chainId, provider);
     }
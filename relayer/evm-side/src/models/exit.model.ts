@@ -95,6 +95,25 @@ export class ExitModel {
   @Column({ name: 'transaction_id', type: 'text', nullable: true })
   txId?: string;
 
+  // Bridge note of a miden exit
+  @Column({ name: 'note_id', type: 'text', nullable: true })
+  noteId?: string;
+
+  @Column({
+    name: 'deposit_count',
+    type: 'bigint',
+    nullable: true,
+    transformer: {
+      from(value: string | null) {
+        return value == null ? null : BigInt(value);
+      },
+      to(value: bigint | null) {
+        return value == null ? null : value.toString();
+      },
+    },
+  })
+  depositCount?: bigint;
+
   @Column({ name: 'block_number', type: 'int', nullable: false })
   blockNumber!: number;
 }
@@ -77,6 +77,7 @@ export class PollerService {
 
     const exits: Array<Partial<ExitModel>> = events.map(([asset, message]) => ({
       txId: asset.tx,
+      depositCount: asset.depositCount,
       assetAddress: message.metadata.assetOriginalAddr,
       assetOrigin: {
         chainId: message.metadata.assetOriginalNetwork,
@@ -53,6 +53,27 @@ export class RpcService {
     return this.providers.get(chainId).getBlockNumber();
   }
 
+  // Bridge events of a single transaction, to recover what the exits saved before some of
+  // their fields were stored lack
+  async getTransactionBridgeEvents(
+    chainId: bigint,
+    txHash: string,
+  ): Promise<Array<[AssetBridgeEvent, MessageBridgeEvent]>> {
+    const receipt = await this.providers
+      .get(chainId)
+      .getTransactionReceipt(txHash);
+    if (receipt == null) {
+      return [];
+    }
+
+    const events = await this.getBridgeEvents(
+      chainId,
+      receipt.blockNumber,
+      receipt.blockNumber,
+    );
+    return events.filter(([asset]) => asset.tx === txHash);
+  }
+
   async getBridgeEvents(
     chainId: bigint,
     blockStart: number,
@@ -15,7 +15,6 @@ import {
 } from 'src/config';
 import { RepositoriesModule } from 'src/repositories/repositories.module';
 import { ExitRepository } from 'src/repositories/services/exit.repository';
-import { ScansRepository } from 'src/repositories/services/scans.repository';
 
 function generateKey(chainId): string {
   return `miden-poller-${chainId}`;
@@ -24,14 +23,14 @@ function generateKey(chainId): string {
 function generateProvider(chainId): FactoryProvider<PollerService> {
   return {
     provide: generateKey(chainId),
-    useFactory(rpcs: Map<bigint, MidenApiService>, exits, scans, config) {
+    useFactory(rpcs: Map<bigint, MidenApiService>, exits, config) {
       const rpc = rpcs.get(chainId);
       if (rpc == null) {
         throw new Error(`Unknown miden chain with chainId: ${chainId}`);
       }
-      return new PollerService(chainId, rpc, exits, scans, config);
+      return new PollerService(chainId, rpc, exits, config);
     },
-    inject: [MIDEN_RPCS, ExitRepository, ScansRepository, MainConfigService],
+    inject: [MIDEN_RPCS, ExitRepository, MainConfigService],
   };
 }
 
@@ -42,16 +41,15 @@ function generateAsyncProvider(index): FactoryProvider<PollerService> {
       config: MainConfigService,
       rpcs: Map<bigint, MidenApiService>,
       exits,
-      scans,
     ) {
       const chainId = config.getMidenChainIds()[index];
       const rpc = rpcs.get(chainId);
       if (rpc == null) {
         throw new Error(`Unknown miden chain with chainId: ${chainId}`);
       }
-      return new PollerService(chainId, rpc, exits, scans, config);
+      return new PollerService(chainId, rpc, exits, config);
     },
-    inject: [MainConfigService, MIDEN_RPCS, ExitRepository, ScansRepository],
+    inject: [MainConfigService, MIDEN_RPCS, ExitRepository],
   };
 }
 
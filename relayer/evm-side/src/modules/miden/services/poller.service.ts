@@ -1,5 +1,6 @@
 import { Logger } from '@nestjs/common';
 import { Cron, CronExpression } from '@nestjs/schedule';
+import type { components } from 'api';
 import BigNumber from 'bignumber.js';
 import {
   getMidenStartBlockScanEnvVarKey,
@@ -7,7 +8,12 @@ import {
   MidenApiService,
 } from 'src/config';
 import { ChainRef } from 'src/models/exit.model';
-import { ExitRepository, ScansRepository } from 'src/repositories';
+import { ExitRepository } from 'src/repositories';
+
+type ExitEvent = components['schemas']['ExitEvent'];
+
+// Exits requested per `/poll` page
+const POLL_PAGE_SIZE = 100;
 
 export class PollerService {
   private readonly logger;
@@ -20,7 +26,6 @@ export class PollerService {
     private readonly chainId: bigint,
     private readonly rpc: MidenApiService,
     private readonly exits: ExitRepository,
-    private readonly scans: ScansRepository,
     config: MainConfigService,
   ) {
     this.logger = new Logger(`MidenPollerService (${chainId})`);
@@ -39,21 +44,31 @@ export class PollerService {
     return 'evm';
   }
 
+  // Follows the exits the relayer has not seen acknowledged rather than their blocks: an exit
+  // whose note the relayer resolves late keeps its block, but is still delivered
   @Cron(CronExpression.EVERY_10_SECONDS, { waitForCompletion: true })
   async poll() {
-    const lastScannedHeight = await this.scans.getLastScannedBlockFor(
-      this.chainId,
-    );
-
-    const startBlock = Math.max(lastScannedHeight + 1, this.startScanBlock);
+    let events: Array<ExitEvent>;
+    do {
+      ({ events } = await this.rpc.pollExits(POLL_PAGE_SIZE));
+      if (events.length === 0) {
+        return;
+      }
 
-    const { chainTip, events } = await this.rpc.pollExits(startBlock);
+      await this.saveExits(
+        events.filter((exit) => exit.blockNumber >= this.startScanBlock),
+      );
+      await this.rpc.acknowledgeExits(events.map((exit) => exit.noteId));
+    } while (events.length === POLL_PAGE_SIZE);
+  }
 
+  private async saveExits(events: Array<ExitEvent>) {
     this.logger.log(`Found ${events.length} exits from chain`);
     await this.exits.tx(async (em) => {
       for (const exit of events) {
-        await this.exits.insertExit(
+        await this.exits.insertMidenExit(
           {
+            noteId: exit.noteId,
             from: {
               chainId: this.chainId,
               chainKind: 'miden',
@@ -78,19 +93,6 @@ export class PollerService {
           em,
         );
       }
-      await this.scans.insertScan(
-        {
-          chain: {
-            chainId: this.chainId,
-            chainKind: 'miden',
-          },
-          startBlock,
-          endBlock: chainTip,
-        },
-        em,
-      );
-
-      this.logger.log(`Scan to block ${chainTip} saved`);
     });
   }
 }
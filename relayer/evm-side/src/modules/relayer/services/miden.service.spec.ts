@@ -0,0 +1,94 @@
+import BigNumber from 'bignumber.js';
+import { MidenApiService } from 'src/config';
+import { ExitModel } from 'src/models/exit.model';
+import { RpcService } from 'src/modules/evm/services/rpc.service';
+import { MidenRelayerService } from './miden.service';
+
+const SEPOLIA = 11155111n;
+const MIDEN = 9966n;
+const TX_HASH =
+  '0x5c0e7a6b0d3f2e1a9c8b7a6f5e4d3c2b1a0f9e8d7c6b5a4f3e2d1c0b9a8f7e6d';
+const SENDER = '0xAb348cB6a2bF1Ae152c793E091ff0545CF0aD7B7';
+
+function legacyExit(): ExitModel {
+  const exit = new ExitModel();
+  exit.id = '1';
+  exit.from = { chainId: SEPOLIA, chainKind: 'evm' };
+  exit.to = { chainId: MIDEN, chainKind: 'miden' };
+  exit.assetOrigin = { chainId: SEPOLIA, chainKind: 'evm' };
+  exit.assetAddress = '0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238';
+  exit.assetAmount = new BigNumber(1000);
+  exit.assetSymbol = 'USDC';
+  exit.assetDecimals = 6;
+  exit.sender = SENDER;
+  exit.calldata = '0x01';
+  exit.txId = TX_HASH;
+  exit.blockNumber = 10;
+  return exit;
+}
+
+function deposit(depositCount: bigint, amount: bigint) {
+  return [
+    {
+      originAddress: SENDER.toLowerCase(),
+      destinationNetwork: MIDEN,
+      amount,
+      depositCount,
+      tx: TX_HASH,
+    },
+    {},
+  ];
+}
+
+describe('MidenRelayerService', () => {
+  let send: jest.Mock;
+  let getTransactionBridgeEvents: jest.Mock;
+  let relayer: MidenRelayerService;
+
+  beforeEach(() => {
+    send = jest.fn().mockResolvedValue({ noteId: '0xnote' });
+    getTransactionBridgeEvents = jest.fn();
+    relayer = new MidenRelayerService(
+      new Map([[MIDEN, { send } as unknown as MidenApiService]]),
+      { getTransactionBridgeEvents } as unknown as RpcService,
+    );
+  });
+
+  it('should send the stored deposit count', async () => {
+    const exit = legacyExit();
+    exit.depositCount = 7n;
+
+    await relayer.relay(exit);
+
+    expect(getTransactionBridgeEvents).not.toHaveBeenCalled();
+    expect(send.mock.calls[0][0].deposit).toEqual({
+      originNetwork: Number(SEPOLIA),
+      txHash: TX_HASH,
+      depositCount: 7,
+    });
+  });
+
+  it('should read the deposit count of a legacy exit from its transaction', async () => {
+    getTransactionBridgeEvents.mockResolvedValue([
+      deposit(4n, 500n),
+      deposit(5n, 1000n),
+    ]);
+
+    await relayer.relay(legacyExit());
+
+    expect(getTransactionBridgeEvents).toHaveBeenCalledWith(SEPOLIA, TX_HASH);
+    expect(send.mock.calls[0][0].deposit.depositCount).toBe(5);
+  });
+
+  it('should not relay a legacy exit without a single matching deposit', async () => {
+    getTransactionBridgeEvents.mockResolvedValue([
+      deposit(4n, 1000n),
+      deposit(5n, 1000n),
+    ]);
+
+    await expect(relayer.relay(legacyExit())).rejects.toThrow(
+      'Exit with id 1 has no deposit count and 2 matching deposits',
+    );
+    expect(send).not.toHaveBeenCalled();
+  });
+});
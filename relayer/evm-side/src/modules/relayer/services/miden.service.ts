@@ -2,6 +2,7 @@ import { Inject, Injectable, Logger } from '@nestjs/common';
 import { ZeroAddress } from 'ethers';
 import { MIDEN_RPCS, MidenApiService } from 'src/config';
 import { ExitModel } from 'src/models/exit.model';
+import { RpcService } from 'src/modules/evm/services/rpc.service';
 
 @Injectable()
 export class MidenRelayerService {
@@ -10,6 +11,7 @@ export class MidenRelayerService {
   constructor(
     @Inject(MIDEN_RPCS)
     private readonly midenRpcs: Map<bigint, MidenApiService>,
+    private readonly evmRpc: RpcService,
   ) {}
 
   async relay(exit: ExitModel) {
@@ -17,12 +19,22 @@ export class MidenRelayerService {
     if (rpc == null) {
       throw new Error(`Unknown miden chain with chainId: ${exit.to.chainId}`);
     }
+    // The relayer mints a deposit only once, whatever the number of requests
+    if (exit.txId == null) {
+      throw new Error(`Exit with id ${exit.id} has no deposit transaction`);
+    }
+    const depositCount = await this.getDepositCount(exit);
     const isWethAsset =
       exit.assetOrigin.chainId === 0n && exit.assetAddress === ZeroAddress;
     this.logger.debug(
       `Exit with id ${exit.id} is about to relay to miden chain`,
     );
     const response = await rpc.send({
+      deposit: {
+        originNetwork: Number(exit.from.chainId),
+        txHash: exit.txId,
+        depositCount: Number(depositCount),
+      },
       amount: exit.assetAmount,
       asset: {
         address: exit.assetAddress,
@@ -37,4 +49,32 @@ export class MidenRelayerService {
       `Deposit relayed to the miden with note id: ${response.noteId}`,
     );
   }
+
+  // Exits saved before their deposit count was stored lack it, it is read back from the bridge
+  // event of their deposit transaction
+  private async getDepositCount(exit: ExitModel): Promise<bigint> {
+    if (exit.depositCount != null) {
+      return exit.depositCount;
+    }
+
+    const deposits = (
+      await this.evmRpc.getTransactionBridgeEvents(exit.from.chainId, exit.txId)
+    ).filter(
+      ([asset]) =>
+        asset.destinationNetwork === exit.to.chainId &&
+        asset.originAddress.toLowerCase() === exit.sender?.toLowerCase() &&
+        exit.assetAmount.isEqualTo(asset.amount.toString()),
+    );
+    if (deposits.length !== 1) {
+      throw new Error(
+        `Exit with id ${exit.id} has no deposit count and ${deposits.length} matching deposits`,
+      );
+    }
+
+    const [[asset]] = deposits;
+    this.logger.debug(
+      `Exit with id ${exit.id} has deposit count ${asset.depositCount}`,
+    );
+    return asset.depositCount;
+  }
 }
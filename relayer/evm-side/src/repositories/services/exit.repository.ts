@@ -1,8 +1,8 @@
 import { Injectable } from '@nestjs/common';
 import { InjectRepository } from '@nestjs/typeorm';
-import { ExitModel } from 'src/models/exit.model';
+import { ChainRef, ExitModel } from 'src/models/exit.model';
 import { FulfillModel } from 'src/models/fulfill.model';
-import { EntityManager, Repository } from 'typeorm';
+import { EntityManager, Equal, IsNull, Repository } from 'typeorm';
 
 @Injectable()
 export class ExitRepository {
@@ -22,6 +22,35 @@ export class ExitRepository {
     await manager.getRepository(ExitModel).insert(model);
   }
 
+  // Saves a miden exit once, it is delivered again until acknowledged. An exit saved before
+  // the exits carried their note id claims its row instead of being inserted again.
+  async insertMidenExit(
+    model: Partial<ExitModel> & { noteId: string; from: ChainRef },
+    manager: EntityManager = this.inner.manager,
+  ): Promise<void> {
+    const exits = manager.getRepository(ExitModel);
+    if (await exits.existsBy({ noteId: model.noteId })) {
+      return;
+    }
+
+    const legacy = await exits.findOne({
+      where: {
+        noteId: IsNull(),
+        from: { chainId: Equal(model.from.chainId) },
+        blockNumber: model.blockNumber,
+        receiver: model.receiver,
+        assetAddress: model.assetAddress,
+        assetAmount: model.assetAmount,
+      },
+    });
+    if (legacy != null) {
+      await exits.update(legacy.id, { noteId: model.noteId });
+      return;
+    }
+
+    await exits.insert(model);
+  }
+
   async getPendingExitsPage(
     pageSize: number,
     pageNum: number,
@@ -0,0 +1,28 @@
+use rocket::data::ByteUnit;
+use thiserror::Error;
+
+use crate::auth::Permission;
+
+#[derive(Error, Debug)]
+pub enum AuthError {
+    #[error("Missing credentials: expected a bearer API key or a signed request")]
+    MissingCredentials,
+    #[error("Invalid API key")]
+    InvalidCredentials,
+    #[error("Request timestamp is malformed or outside of the allowed clock skew")]
+    InvalidTimestamp,
+    #[error("Invalid request signature")]
+    InvalidSignature,
+    #[error("Request nonce is missing or longer than {0} characters")]
+    InvalidNonce(usize),
+    #[error("Request nonce was already used")]
+    ReplayedRequest,
+    #[error("API key lacks the `{0:?}` permission")]
+    PermissionDenied(Permission),
+    #[error("Request body exceeds the {0} limit")]
+    BodyTooLarge(ByteUnit),
+    #[error("Malformed request body: {0}")]
+    Body(String),
+    #[error("Authenticator is not configured")]
+    NotConfigured,
+}
@@ -0,0 +1,485 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use rocket::data::{self, Data, FromData, Limits};
+use rocket::http::Status;
+use rocket::outcome::Outcome;
+use rocket::request::{self, FromRequest, Request};
+use rocket::serde::de::DeserializeOwned;
+use rocket::serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+mod errors;
+
+pub use errors::AuthError;
+
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+pub const TIMESTAMP_HEADER: &str = "X-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+pub const NONCE_HEADER: &str = "X-Nonce";
+
+/// Longest nonce accepted in `X-Nonce`, enough for a UUID or 32 random bytes in hex.
+pub const MAX_NONCE_LEN: usize = 64;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum Permission {
+    /// Allows minting notes.
+    Mint,
+    /// Allows the read-only routes: polling, chain tip, job and transaction status.
+    Read,
+    /// Allows managing the asset registry.
+    Admin,
+}
+
+/// How an API key authenticates the requests, a key is only accepted in its own mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum KeyMode {
+    /// The secret is sent as `Authorization: Bearer <secret>`.
+    #[default]
+    Bearer,
+    /// The secret is the HMAC key of signed requests and never sent, so it is not a bearer token.
+    Signed,
+}
+
+/// An API key as configured in `Config`.
+///
+/// The secret is either the bearer token or the HMAC key for signed requests, depending on the
+/// mode of the key.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct ApiKey {
+    pub id: String,
+    secret: String,
+    #[serde(default)]
+    pub mode: KeyMode,
+    pub permissions: Vec<Permission>,
+}
+
+impl ApiKey {
+    pub fn new(id: String, secret: String, mode: KeyMode, permissions: Vec<Permission>) -> Self {
+        Self { id, secret, mode, permissions }
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+/// Authentication headers of a signed request.
+#[derive(Debug, Clone, Copy)]
+pub struct SignedHeaders<'a> {
+    pub key_id: &'a str,
+    pub timestamp: &'a str,
+    pub nonce: &'a str,
+    pub signature: &'a str,
+}
+
+/// Authenticates requests either by a bearer API key or by an HMAC-SHA256 signature, according
+/// to the [`KeyMode`] of the key.
+///
+/// Signed requests carry the key id in `X-Api-Key`, the unix timestamp in `X-Timestamp`, a
+/// unique nonce in `X-Nonce` and the hex encoded signature of
+/// `"{timestamp}\n{nonce}\n{METHOD}\n{uri}\n{body}"` in `X-Signature`.
+///
+/// A signed request is only accepted once: the nonces are remembered for as long as their
+/// timestamp is within the clock skew, older requests are rejected by their timestamp.
+pub struct Authenticator {
+    keys: Vec<ApiKey>,
+    max_clock_skew_secs: u64,
+    /// Nonces of the accepted signed requests per key id, with the time they expire at.
+    seen_nonces: Mutex<HashMap<(String, String), u64>>,
+}
+
+impl Authenticator {
+    pub fn new(keys: Vec<ApiKey>, max_clock_skew_secs: u64) -> Self {
+        Self { keys, max_clock_skew_secs, seen_nonces: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn has_keys(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    pub fn authenticate_bearer(&self, token: &str) -> Result<&ApiKey, AuthError> {
+        self.keys
+            .iter()
+            .filter(|key| key.mode == KeyMode::Bearer)
+            .find(|key| constant_time_eq(key.secret.as_bytes(), token.as_bytes()))
+            .ok_or(AuthError::InvalidCredentials)
+    }
+
+    pub fn authenticate_signature(
+        &self,
+        headers: SignedHeaders<'_>,
+        method: &str,
+        uri: &str,
+        body: &[u8],
+    ) -> Result<&ApiKey, AuthError> {
+        let SignedHeaders { key_id, timestamp, nonce, signature } = headers;
+        let key = self
+            .keys
+            .iter()
+            .find(|key| key.mode == KeyMode::Signed && key.id == key_id)
+            .ok_or(AuthError::InvalidCredentials)?;
+
+        let now = now_seconds();
+        let request_time: u64 = timestamp.parse().map_err(|_| AuthError::InvalidTimestamp)?;
+        if now.abs_diff(request_time) > self.max_clock_skew_secs {
+            return Err(AuthError::InvalidTimestamp);
+        }
+        if nonce.is_empty() || nonce.len() > MAX_NONCE_LEN {
+            return Err(AuthError::InvalidNonce(MAX_NONCE_LEN));
+        }
+
+        let signature = hex::decode(signature).map_err(|_| AuthError::InvalidSignature)?;
+        signing_mac(&key.secret, timestamp, nonce, method, uri, body)
+            .verify_slice(&signature)
+            .map_err(|_| AuthError::InvalidSignature)?;
+
+        // Only a valid signature records its nonce, so forged requests cannot burn nonces
+        let mut seen_nonces = self.seen_nonces.lock().expect("Nonce cache lock to not be poisoned");
+        seen_nonces.retain(|_, expires_at| *expires_at >= now);
+        let expires_at = request_time + self.max_clock_skew_secs;
+        if seen_nonces.insert((key.id.clone(), nonce.to_string()), expires_at).is_some() {
+            return Err(AuthError::ReplayedRequest);
+        }
+
+        Ok(key)
+    }
+
+    /// Authenticates the request from its headers, `body` being the raw request body.
+    fn authenticate(&self, request: &Request<'_>, body: &[u8]) -> Result<&ApiKey, AuthError> {
+        let headers = request.headers();
+
+        if let Some(authorization) = headers.get_one("Authorization") {
+            let token =
+                authorization.strip_prefix("Bearer ").ok_or(AuthError::InvalidCredentials)?;
+            return self.authenticate_bearer(token.trim());
+        }
+
+        match (
+            headers.get_one(API_KEY_HEADER),
+            headers.get_one(TIMESTAMP_HEADER),
+            headers.get_one(SIGNATURE_HEADER),
+        ) {
+            (Some(key_id), Some(timestamp), Some(signature)) => self.authenticate_signature(
+                SignedHeaders {
+                    key_id,
+                    timestamp,
+                    nonce: headers.get_one(NONCE_HEADER).unwrap_or_default(),
+                    signature,
+                },
+                request.method().as_str(),
+                &request.uri().to_string(),
+                body,
+            ),
+            _ => Err(AuthError::MissingCredentials),
+        }
+    }
+
+    fn authorize(
+        &self,
+        request: &Request<'_>,
+        body: &[u8],
+        permission: Permission,
+    ) -> Result<ApiKey, (Status, AuthError)> {
+        let key = self.authenticate(request, body).map_err(|e| (Status::Unauthorized, e))?;
+
+        if !key.has_permission(permission) {
+            return Err((Status::Forbidden, AuthError::PermissionDenied(permission)));
+        }
+
+        Ok(key.clone())
+    }
+}
+
+/// Computes the hex encoded request signature, as expected in `X-Signature`.
+pub fn sign_request(
+    secret: &str,
+    timestamp: &str,
+    nonce: &str,
+    method: &str,
+    uri: &str,
+    body: &[u8],
+) -> String {
+    hex::encode(signing_mac(secret, timestamp, nonce, method, uri, body).finalize().into_bytes())
+}
+
+fn signing_mac(
+    secret: &str,
+    timestamp: &str,
+    nonce: &str,
+    method: &str,
+    uri: &str,
+    body: &[u8],
+) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{timestamp}\n{nonce}\n{method}\n{uri}\n").as_bytes());
+    mac.update(body);
+    mac
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn now_seconds() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards").as_secs()
+}
+
+// SCOPES
+// ================================================================================================
+
+/// Permission required by a guarded route.
+pub trait Scope: Send + Sync + 'static {
+    const PERMISSION: Permission;
+}
+
+pub struct MintScope;
+
+impl Scope for MintScope {
+    const PERMISSION: Permission = Permission::Mint;
+}
+
+pub struct ReadScope;
+
+impl Scope for ReadScope {
+    const PERMISSION: Permission = Permission::Read;
+}
+
+pub struct AdminScope;
+
+impl Scope for AdminScope {
+    const PERMISSION: Permission = Permission::Admin;
+}
+
+// GUARDS
+// ================================================================================================
+
+/// Request guard for routes without a body. Signed requests sign an empty body.
+pub struct Authorized<S: Scope> {
+    pub key: ApiKey,
+    _scope: PhantomData<S>,
+}
+
+#[rocket::async_trait]
+impl<'r, S: Scope> FromRequest<'r> for Authorized<S> {
+    type Error = AuthError;
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let Some(authenticator) = request.rocket().state::<Authenticator>() else {
+            return Outcome::Error((Status::InternalServerError, AuthError::NotConfigured));
+        };
+
+        match authenticator.authorize(request, &[], S::PERMISSION) {
+            Ok(key) => Outcome::Success(Authorized { key, _scope: PhantomData }),
+            Err(e) => Outcome::Error(e),
+        }
+    }
+}
+
+/// Data guard for JSON routes. The signature of signed requests covers the raw body.
+pub struct AuthorizedJson<T, S: Scope> {
+    pub key: ApiKey,
+    value: T,
+    _scope: PhantomData<S>,
+}
+
+impl<T, S: Scope> AuthorizedJson<T, S> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, S: Scope> std::ops::Deref for AuthorizedJson<T, S> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, T: DeserializeOwned, S: Scope> FromData<'r> for AuthorizedJson<T, S> {
+    type Error = AuthError;
+
+    async fn from_data(request: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let Some(authenticator) = request.rocket().state::<Authenticator>() else {
+            return Outcome::Error((Status::InternalServerError, AuthError::NotConfigured));
+        };
+
+        let limit = request.limits().get("json").unwrap_or(Limits::JSON);
+        let body = match data.open(limit).into_bytes().await {
+            Ok(body) if body.is_complete() => body.into_inner(),
+            Ok(_) => {
+                return Outcome::Error((
+                    Status::PayloadTooLarge,
+                    AuthError::BodyTooLarge(limit),
+                ));
+            },
+            Err(e) => return Outcome::Error((Status::BadRequest, AuthError::Body(e.to_string()))),
+        };
+
+        let key = match authenticator.authorize(request, &body, S::PERMISSION) {
+            Ok(key) => key,
+            Err(e) => return Outcome::Error(e),
+        };
+
+        match rocket::serde::json::from_slice(&body) {
+            Ok(value) => Outcome::Success(AuthorizedJson { key, value, _scope: PhantomData }),
+            Err(e) => Outcome::Error((Status::UnprocessableEntity, AuthError::Body(e.to_string()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ApiKey, AuthError, Authenticator, KeyMode, Permission, SignedHeaders, now_seconds,
+        sign_request,
+    };
+
+    fn authenticator() -> Authenticator {
+        Authenticator::new(
+            vec![
+                ApiKey::new(
+                    "minter".into(),
+                    "mint-secret".into(),
+                    KeyMode::Signed,
+                    vec![Permission::Mint],
+                ),
+                ApiKey::new(
+                    "reader".into(),
+                    "read-secret".into(),
+                    KeyMode::Signed,
+                    vec![Permission::Read],
+                ),
+                ApiKey::new(
+                    "viewer".into(),
+                    "view-secret".into(),
+                    KeyMode::Bearer,
+                    vec![Permission::Read],
+                ),
+            ],
+            60,
+        )
+    }
+
+    #[test]
+    fn should_authenticate_bearer_key() {
+        let authenticator = authenticator();
+        let key = authenticator.authenticate_bearer("view-secret").unwrap();
+        assert_eq!(key.id, "viewer");
+        assert!(key.has_permission(Permission::Read));
+        assert!(!key.has_permission(Permission::Mint));
+        assert!(matches!(
+            authenticator.authenticate_bearer("view-secre"),
+            Err(AuthError::InvalidCredentials)
+        ));
+    }
+
+    #[test]
+    fn should_only_accept_a_key_in_its_mode() {
+        let authenticator = authenticator();
+        // the HMAC key of a signed key is not a bearer token
+        assert!(matches!(
+            authenticator.authenticate_bearer("read-secret"),
+            Err(AuthError::InvalidCredentials)
+        ));
+
+        let timestamp = now_seconds().to_string();
+        let signature = sign_request("view-secret", &timestamp, "n1", "GET", "/poll", &[]);
+        let headers = SignedHeaders {
+            key_id: "viewer",
+            timestamp: &timestamp,
+            nonce: "n1",
+            signature: &signature,
+        };
+        assert!(matches!(
+            authenticator.authenticate_signature(headers, "GET", "/poll", &[]),
+            Err(AuthError::InvalidCredentials)
+        ));
+    }
+
+    #[test]
+    fn should_verify_signed_body() {
+        let authenticator = authenticator();
+        let timestamp = now_seconds().to_string();
+        let body = br#"{"amount":1}"#;
+        let signature = sign_request("mint-secret", &timestamp, "n1", "POST", "/mint", body);
+        let headers = SignedHeaders {
+            key_id: "minter",
+            timestamp: &timestamp,
+            nonce: "n1",
+            signature: &signature,
+        };
+
+        assert!(matches!(
+            authenticator.authenticate_signature(headers, "POST", "/mint", br#"{"amount":2}"#),
+            Err(AuthError::InvalidSignature)
+        ));
+        assert!(matches!(
+            authenticator.authenticate_signature(
+                SignedHeaders { key_id: "reader", ..headers },
+                "POST",
+                "/mint",
+                body
+            ),
+            Err(AuthError::InvalidSignature)
+        ));
+
+        let key = authenticator.authenticate_signature(headers, "POST", "/mint", body).unwrap();
+        assert_eq!(key.id, "minter");
+    }
+
+    #[test]
+    fn should_reject_replayed_signature() {
+        let authenticator = authenticator();
+        let timestamp = now_seconds().to_string();
+        let signature = sign_request("read-secret", &timestamp, "n1", "GET", "/poll", &[]);
+        let headers = SignedHeaders {
+            key_id: "reader",
+            timestamp: &timestamp,
+            nonce: "n1",
+            signature: &signature,
+        };
+
+        authenticator.authenticate_signature(headers, "GET", "/poll", &[]).unwrap();
+        assert!(matches!(
+            authenticator.authenticate_signature(headers, "GET", "/poll", &[]),
+            Err(AuthError::ReplayedRequest)
+        ));
+
+        let signature = sign_request("read-secret", &timestamp, "", "GET", "/poll", &[]);
+        let headers = SignedHeaders { nonce: "", signature: &signature, ..headers };
+        assert!(matches!(
+            authenticator.authenticate_signature(headers, "GET", "/poll", &[]),
+            Err(AuthError::InvalidNonce(_))
+        ));
+    }
+
+    #[test]
+    fn should_reject_stale_signature() {
+        let authenticator = authenticator();
+        let timestamp = (now_seconds() - 120).to_string();
+        let signature = sign_request("mint-secret", &timestamp, "n1", "GET", "/poll?from=1", &[]);
+        let headers = SignedHeaders {
+            key_id: "minter",
+            timestamp: &timestamp,
+            nonce: "n1",
+            signature: &signature,
+        };
+
+        assert!(matches!(
+            authenticator.authenticate_signature(headers, "GET", "/poll?from=1", &[]),
+            Err(AuthError::InvalidTimestamp)
+        ));
+    }
+}
@@ -0,0 +1,105 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use miden_client::utils::Deserializable;
+use miden_objects::account::AccountFile;
+use rocket::serde::json::to_pretty_string;
+
+use crate::config::Config;
+use crate::onchain::OnchainClient;
+use crate::onchain::client::build_client;
+use crate::onchain::faucets::{deploy_faucet, import_faucet};
+use crate::onchain::supervisor::worker_index;
+use crate::onchain::supply::supply_report;
+use crate::store::Store;
+
+#[derive(Parser)]
+#[command(about = "Miden side of the bridge relayer")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Runs the relayer, the default.
+    Serve,
+    /// Manages the wrapper faucets. Stop the relayer first, both use the same stores.
+    #[command(subcommand)]
+    Faucet(FaucetCommand),
+    /// Prints the supply reconciliation of the registered assets, failing on any discrepancy. Can
+    /// run next to the relayer.
+    Supply,
+}
+
+#[derive(Subcommand)]
+pub enum FaucetCommand {
+    /// Creates the wrapper faucet of a registered asset ahead of its first mint.
+    Deploy {
+        #[arg(long)]
+        origin_network: u32,
+        #[arg(long)]
+        origin_address: String,
+    },
+    /// Imports an existing wrapper faucet from an account file exported with its keys, after
+    /// checking its keys and its on-chain code and origin slot.
+    Import {
+        /// Account file, as exported by `miden export --account`.
+        #[arg(long)]
+        account_file: PathBuf,
+        #[arg(long)]
+        origin_network: u32,
+        #[arg(long)]
+        origin_address: String,
+    },
+}
+
+pub async fn run_faucet_command(config: &Config, command: FaucetCommand) -> Result<(), Box<dyn Error>> {
+    let onchain = OnchainClient::new(config.rpc_url(), config.rpc_timeout_ms());
+    // The faucet goes to the client store of the worker minting it
+    let (FaucetCommand::Deploy { origin_network, origin_address }
+    | FaucetCommand::Import { origin_network, origin_address, .. }) = &command;
+    let worker = worker_index(*origin_network, origin_address, config.mint_workers());
+    let (mut execution_client, keystore) =
+        build_client(config, onchain.rpc.clone(), worker).await?;
+    let store = Store::new(config.assets_store_path()).await?;
+
+    let faucet_id = match command {
+        FaucetCommand::Deploy { origin_network, origin_address } => {
+            deploy_faucet(&mut execution_client, &keystore, &store, origin_network, &origin_address)
+                .await?
+        },
+        FaucetCommand::Import { account_file, origin_network, origin_address } => {
+            let account_file = AccountFile::read_from_bytes(&std::fs::read(&account_file)?)?;
+            import_faucet(
+                &mut execution_client,
+                &keystore,
+                &*onchain.rpc,
+                &store,
+                account_file,
+                origin_network,
+                &origin_address,
+            )
+            .await?
+        },
+    };
+
+    println!("Faucet {}", faucet_id.to_hex());
+
+    Ok(())
+}
+
+pub async fn run_supply_report(config: &Config) -> Result<(), Box<dyn Error>> {
+    let onchain = OnchainClient::new(config.rpc_url(), config.rpc_timeout_ms());
+    let store = Store::new(config.assets_store_path()).await?;
+
+    let report = supply_report(&*onchain.rpc, &store).await?;
+    println!("{}", to_pretty_string(&report)?);
+
+    if !report.balanced {
+        return Err("Supply discrepancies found".into());
+    }
+
+    Ok(())
+}
@@ -1,13 +1,168 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use miden_client::rpc::Endpoint;
+use miden_objects::{MAX_OUTPUT_NOTES_PER_TX, MAX_TX_EXECUTION_CYCLES, MIN_TX_EXECUTION_CYCLES};
 use rocket::serde::Deserialize;
+use thiserror::Error;
+
+use crate::auth::ApiKey;
+use crate::onchain::mint_note::MintNoteType;
+
+const DEFAULT_MAX_CLOCK_SKEW_SECS: u64 = 300;
+const DEFAULT_SYNC_INTERVAL_SECS: u64 = 10;
+const DEFAULT_MAX_SYNC_LAG_BLOCKS: u32 = 20;
+const DEFAULT_MIDEN_STORE_PATH: &str = "./miden_store.sql";
+const DEFAULT_ASSETS_STORE_PATH: &str = "./assets_store.sql";
+const DEFAULT_KEYSTORE_PATH: &str = "./keystore";
+const DEFAULT_COMMAND_CHANNEL_SIZE: usize = 10;
+const DEFAULT_MAX_MINT_BATCH_SIZE: usize = 10;
+const DEFAULT_MINT_WORKERS: usize = 1;
 
+#[derive(Error, Debug, PartialEq)]
+pub enum ConfigError {
+    #[error("Invalid rpc_url `{0}`: {1}")]
+    InvalidRpcUrl(String, String),
+    #[error("`{0}` must be greater than zero")]
+    Zero(&'static str),
+    #[error(
+        "Execution cycles must satisfy {MIN_TX_EXECUTION_CYCLES} <= expected ({expected}) <= max ({max}) <= {MAX_TX_EXECUTION_CYCLES}"
+    )]
+    InvalidExecutionCycles { expected: u32, max: u32 },
+    #[error("`max_mint_batch_size` ({0}) exceeds the {MAX_OUTPUT_NOTES_PER_TX} notes of a transaction")]
+    MintBatchTooLarge(usize),
+    #[error("Directory of `{0}` does not exist")]
+    MissingDirectory(PathBuf),
+}
+
+/// Relayer configuration, read from the `Rocket.toml` profile selected by `ROCKET_PROFILE`
+/// (`testnet`, `devnet`, `local`) and overridden by `ROCKET_`-prefixed environment variables.
 #[derive(Deserialize)]
 #[serde(crate = "rocket::serde")]
 pub struct Config {
     rpc_url: String,
     rpc_timeout_ms: u64,
+    #[serde(default)]
+    api_keys: Vec<ApiKey>,
+    #[serde(default = "default_max_clock_skew_secs")]
+    max_clock_skew_secs: u64,
+    #[serde(default = "default_sync_interval_secs")]
+    sync_interval_secs: u64,
+    #[serde(default = "default_max_sync_lag_blocks")]
+    max_sync_lag_blocks: u32,
+    #[serde(default = "default_miden_store_path")]
+    miden_store_path: PathBuf,
+    #[serde(default = "default_assets_store_path")]
+    assets_store_path: PathBuf,
+    #[serde(default = "default_keystore_path")]
+    keystore_path: PathBuf,
+    #[serde(default = "default_command_channel_size")]
+    command_channel_size: usize,
+    #[serde(default = "default_mint_workers")]
+    mint_workers: usize,
+    #[serde(default = "default_max_tx_execution_cycles")]
+    max_tx_execution_cycles: u32,
+    #[serde(default = "default_expected_tx_execution_cycles")]
+    expected_tx_execution_cycles: u32,
+    #[serde(default)]
+    note_type: MintNoteType,
+    #[serde(default = "default_max_mint_batch_size")]
+    max_mint_batch_size: usize,
+    #[serde(default)]
+    max_mint_batch_wait_ms: u64,
+    #[serde(default)]
+    remote_prover_url: Option<String>,
+}
+
+fn default_max_clock_skew_secs() -> u64 {
+    DEFAULT_MAX_CLOCK_SKEW_SECS
+}
+
+fn default_sync_interval_secs() -> u64 {
+    DEFAULT_SYNC_INTERVAL_SECS
+}
+
+fn default_max_sync_lag_blocks() -> u32 {
+    DEFAULT_MAX_SYNC_LAG_BLOCKS
+}
+
+fn default_miden_store_path() -> PathBuf {
+    DEFAULT_MIDEN_STORE_PATH.into()
+}
+
+fn default_assets_store_path() -> PathBuf {
+    DEFAULT_ASSETS_STORE_PATH.into()
+}
+
+fn default_keystore_path() -> PathBuf {
+    DEFAULT_KEYSTORE_PATH.into()
+}
+
+fn default_command_channel_size() -> usize {
+    DEFAULT_COMMAND_CHANNEL_SIZE
+}
+
+fn default_mint_workers() -> usize {
+    DEFAULT_MINT_WORKERS
+}
+
+fn default_max_mint_batch_size() -> usize {
+    DEFAULT_MAX_MINT_BATCH_SIZE
+}
+
+fn default_max_tx_execution_cycles() -> u32 {
+    MAX_TX_EXECUTION_CYCLES
+}
+
+fn default_expected_tx_execution_cycles() -> u32 {
+    MIN_TX_EXECUTION_CYCLES
+}
+
+/// Checks that the directory a store file is created in exists.
+fn check_parent_directory(path: &Path) -> Result<(), ConfigError> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() && !parent.is_dir() => {
+            Err(ConfigError::MissingDirectory(path.to_path_buf()))
+        },
+        _ => Ok(()),
+    }
 }
 
 impl Config {
+    /// Rejects the settings the relayer would only fail on later, at runtime.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        Endpoint::try_from(self.rpc_url.as_str())
+            .map_err(|e| ConfigError::InvalidRpcUrl(self.rpc_url.clone(), e.to_string()))?;
+
+        if self.rpc_timeout_ms == 0 {
+            return Err(ConfigError::Zero("rpc_timeout_ms"));
+        }
+        if self.sync_interval_secs == 0 {
+            return Err(ConfigError::Zero("sync_interval_secs"));
+        }
+        if self.command_channel_size == 0 {
+            return Err(ConfigError::Zero("command_channel_size"));
+        }
+        if self.mint_workers == 0 {
+            return Err(ConfigError::Zero("mint_workers"));
+        }
+        if self.max_mint_batch_size == 0 {
+            return Err(ConfigError::Zero("max_mint_batch_size"));
+        }
+        if self.max_mint_batch_size > MAX_OUTPUT_NOTES_PER_TX {
+            return Err(ConfigError::MintBatchTooLarge(self.max_mint_batch_size));
+        }
+
+        let (expected, max) = (self.expected_tx_execution_cycles, self.max_tx_execution_cycles);
+        if expected < MIN_TX_EXECUTION_CYCLES || expected > max || max > MAX_TX_EXECUTION_CYCLES {
+            return Err(ConfigError::InvalidExecutionCycles { expected, max });
+        }
+
+        check_parent_directory(&self.miden_store_path)?;
+        check_parent_directory(&self.assets_store_path)?;
+        check_parent_directory(&self.keystore_path)
+    }
+
     pub fn rpc_url(&self) -> String {
         self.rpc_url.clone()
     }
@@ -15,4 +170,139 @@ impl Config {
     pub fn rpc_timeout_ms(&self) -> u64 {
         self.rpc_timeout_ms
     }
+
+    pub fn api_keys(&self) -> Vec<ApiKey> {
+        self.api_keys.clone()
+    }
+
+    /// Maximum allowed difference between the timestamp of a signed request and the local clock.
+    pub fn max_clock_skew_secs(&self) -> u64 {
+        self.max_clock_skew_secs
+    }
+
+    /// How often the worker syncs the client store with the node.
+    pub fn sync_interval(&self) -> Duration {
+        Duration::from_secs(self.sync_interval_secs)
+    }
+
+    /// How many blocks the client store may be behind the node for the relayer to be ready.
+    pub fn max_sync_lag_blocks(&self) -> u32 {
+        self.max_sync_lag_blocks
+    }
+
+    /// Database of the miden client of the worker `worker`: accounts, notes and transactions. The
+    /// first worker uses `miden_store_path`, the others a file suffixed with their index, e.g.
+    /// `miden_store-1.sql`.
+    pub fn miden_store_path(&self, worker: usize) -> PathBuf {
+        if worker == 0 {
+            return self.miden_store_path.clone();
+        }
+
+        let stem = self.miden_store_path.file_stem().unwrap_or_default().to_string_lossy();
+        let file_name = match self.miden_store_path.extension() {
+            Some(extension) => format!("{stem}-{worker}.{}", extension.to_string_lossy()),
+            None => format!("{stem}-{worker}"),
+        };
+
+        self.miden_store_path.with_file_name(file_name)
+    }
+
+    /// Database of the relayer: faucets, mint jobs and exit events.
+    pub fn assets_store_path(&self) -> PathBuf {
+        self.assets_store_path.clone()
+    }
+
+    /// Directory of the faucet secret keys.
+    pub fn keystore_path(&self) -> PathBuf {
+        self.keystore_path.clone()
+    }
+
+    /// How many commands can wait for a worker before the routes answer 503.
+    pub fn command_channel_size(&self) -> usize {
+        self.command_channel_size
+    }
+
+    /// How many workers mint in parallel, each owning the faucets of a share of the assets.
+    pub fn mint_workers(&self) -> usize {
+        self.mint_workers
+    }
+
+    pub fn max_tx_execution_cycles(&self) -> u32 {
+        self.max_tx_execution_cycles
+    }
+
+    pub fn expected_tx_execution_cycles(&self) -> u32 {
+        self.expected_tx_execution_cycles
+    }
+
+    /// Type of the minted notes, for the requests that do not set one.
+    pub fn note_type(&self) -> MintNoteType {
+        self.note_type
+    }
+
+    /// How many mint jobs of a faucet are minted by a single transaction, 1 disables batching.
+    pub fn max_mint_batch_size(&self) -> usize {
+        self.max_mint_batch_size
+    }
+
+    /// Endpoint of the remote transaction prover, the relayer proves locally without one.
+    pub fn remote_prover_url(&self) -> Option<&str> {
+        self.remote_prover_url.as_deref()
+    }
+
+    /// How long the worker waits for more mint requests before minting a batch that is not full.
+    pub fn max_mint_batch_wait(&self) -> Duration {
+        Duration::from_millis(self.max_mint_batch_wait_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rocket::figment::Figment;
+    use rocket::figment::providers::{Format, Toml};
+
+    use super::{Config, ConfigError};
+    use crate::onchain::mint_note::MintNoteType;
+
+    const PROFILES: &str = r#"
+        [default]
+        rpc_timeout_ms = 10000
+
+        [testnet]
+        rpc_url = "https://rpc.testnet.miden.io"
+
+        [local]
+        rpc_url = "http://localhost:57291"
+        note_type = "public"
+        max_tx_execution_cycles = 100
+    "#;
+
+    fn config(profile: &str) -> Config {
+        Figment::from(Toml::string(PROFILES).nested()).select(profile).extract().unwrap()
+    }
+
+    #[test]
+    fn should_read_the_selected_profile() {
+        let testnet = config("testnet");
+        assert_eq!(testnet.rpc_url(), "https://rpc.testnet.miden.io");
+        assert_eq!(testnet.command_channel_size(), 10);
+        assert_eq!(testnet.mint_workers(), 1);
+        assert_eq!(testnet.max_mint_batch_size(), 10);
+        assert_eq!(testnet.max_mint_batch_wait(), std::time::Duration::ZERO);
+        assert_eq!(testnet.validate(), Ok(()));
+
+        let local = config("local");
+        assert_eq!(local.rpc_url(), "http://localhost:57291");
+        assert_eq!(local.note_type(), MintNoteType::Public);
+        assert!(matches!(local.validate(), Err(ConfigError::InvalidExecutionCycles { .. })));
+    }
+
+    #[test]
+    fn should_give_every_worker_its_own_client_store() {
+        let testnet = config("testnet");
+        assert_eq!(testnet.miden_store_path(0), PathBuf::from("./miden_store.sql"));
+        assert_eq!(testnet.miden_store_path(2), PathBuf::from("./miden_store-2.sql"));
+    }
 }
@@ -0,0 +1,57 @@
+use rocket::serde::Serialize;
+
+/// Outcome of the readiness checks of the relayer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "camelCase")]
+pub struct Readiness {
+    pub ready: bool,
+    /// Whether the node answered the chain tip request of the last sync.
+    pub node_reachable: bool,
+    pub store_writable: bool,
+    /// Whether every worker is running and takes commands.
+    pub worker_up: bool,
+    pub sync_height: Option<u32>,
+    pub chain_tip: Option<u32>,
+    /// Blocks the client store is behind the node, unknown until both heights are.
+    pub sync_lag: Option<u32>,
+}
+
+impl Readiness {
+    /// The relayer is ready when every check passes and the client store is at most
+    /// `max_sync_lag` blocks behind the node.
+    pub fn new(
+        chain_tip: Option<u32>,
+        store_writable: bool,
+        worker_up: bool,
+        sync_height: Option<u32>,
+        max_sync_lag: u32,
+    ) -> Self {
+        let sync_lag = chain_tip.zip(sync_height).map(|(tip, height)| tip.saturating_sub(height));
+        let ready = store_writable && worker_up && sync_lag.is_some_and(|lag| lag <= max_sync_lag);
+
+        Readiness {
+            ready,
+            node_reachable: chain_tip.is_some(),
+            store_writable,
+            worker_up,
+            sync_height,
+            chain_tip,
+            sync_lag,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Readiness;
+
+    #[test]
+    fn should_require_every_check_and_a_small_sync_lag() {
+        assert!(Readiness::new(Some(105), true, true, Some(100), 5).ready);
+        assert!(!Readiness::new(Some(106), true, true, Some(100), 5).ready);
+        assert!(!Readiness::new(None, true, true, Some(100), 5).ready);
+        assert!(!Readiness::new(Some(100), true, true, None, 5).ready);
+        assert!(!Readiness::new(Some(100), false, true, Some(100), 5).ready);
+        assert!(!Readiness::new(Some(100), true, false, Some(100), 5).ready);
+    }
+}
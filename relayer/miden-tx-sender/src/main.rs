@@ -1,47 +1,50 @@
 extern crate dotenv;
 #[macro_use]
 extern crate rocket;
+mod auth;
+mod cli;
 mod config;
+mod health;
+mod metrics;
 mod onchain;
 mod store;
-mod utils;
 
+use std::convert::Infallible;
 use std::error::Error;
 use rocket::State as RocketState;
 use std::sync::Arc;
 
+use crate::auth::{AdminScope, Authenticator, Authorized, AuthorizedJson, MintScope, ReadScope};
+use crate::cli::{run_faucet_command, run_supply_report, Cli, Command};
 use crate::config::Config;
-use crate::onchain::client::{client_process_loop, ClientCommand};
-use crate::onchain::mint_note::{mint_asset, MintArgs, MintedNote};
+use crate::health::Readiness;
+use crate::metrics::Metrics;
+use crate::onchain::client::ClientCommand;
+use crate::onchain::limits::MintLimits;
+use crate::onchain::mint_job::{MintJob, MintJobState};
+use crate::onchain::mint_note::{MintArgs, MintNoteType, MintedNote};
 use crate::onchain::OnchainClient;
+use crate::onchain::asset::{AssetRegistration, RegisteredAsset};
+use crate::onchain::errors::OnchainError;
+use crate::onchain::supervisor::WorkerPool;
+use crate::onchain::supply::{supply_report, SupplyReport};
+use crate::onchain::sync::{SyncStatus, SyncStatusReceiver};
+use crate::onchain::tracker::TrackedTransaction;
+use crate::store::Store;
 use dotenv::dotenv;
-use miden_bridge::accounts::token_wrapper::TokenWrapperAccount;
-use miden_client::account::component::{BasicFungibleFaucet, BasicWallet, AuthRpoFalcon512};
-use miden_client::keystore::FilesystemKeyStore;
-use miden_client::note::get_input_note_with_id_prefix;
-use miden_client::rpc::NodeRpcClient;
-use miden_client::store::sqlite_store::SqliteStore;
-use miden_client::store::{NoteExportType, NoteFilter};
-use miden_client::transaction::{TransactionRequest, TransactionRequestBuilder};
-use miden_client::utils::Deserializable;
-use miden_client::{Client, ClientError, Felt};
-use miden_crypto::dsa::rpo_falcon512::SecretKey;
-use miden_crypto::rand::RpoRandomCoin;
-use miden_objects::{Word, account::{
-    Account, AccountBuilder, AccountId, AccountStorageMode, AccountType, AuthSecretKey,
-}};
-use miden_objects::asset::{FungibleAsset, TokenSymbol};
-use miden_objects::block::BlockNumber;
-use miden_objects::note::{Note, NoteFile, NoteType};
-use miden_objects::utils::{ReadAdapter, parse_hex_string_as_word};
-use rand::rngs::{StdRng, ThreadRng};
-use rand::{rng, Rng, RngCore};
 use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::response::stream::{Event, EventStream};
+use rocket::tokio::select;
+use rocket::tokio::sync::broadcast::error::RecvError;
+use rocket::{Build, Rocket, Shutdown};
+use clap::Parser;
 use rocket::serde::{json::Json, Deserialize, Serialize};
-use tokio::runtime::Runtime;
-use tokio::sync::mpsc::{Receiver, Sender};
-use log::warn;
-use crate::onchain::poll_events::PolledEvents;
+use log::{info, warn};
+use crate::onchain::poll_events::{
+    DEFAULT_POLL_LIMIT, EXIT_STREAM_CAPACITY, ExitAck, ExitCursor, ExitEventSender, ExitFilter,
+    ExitQuery, MAX_POLL_LIMIT, PolledEvents,
+};
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(crate = "rocket::serde")]
@@ -49,93 +52,641 @@ pub struct ErrorResponse {
     error: String,
 }
 
+/// Answer of a mint request: the minted note, or the job of a deposit pending approval.
+#[derive(Responder)]
+enum MintResponse {
+    Minted(Json<MintedNote>),
+    /// The job is held, its error tells the exceeded limit. It is minted once an admin approves it.
+    #[response(status = 202)]
+    Held(Json<MintJob>),
+}
+
 #[post("/mint", format = "json", data = "<mint_args>")]
 async fn mint_note(
-    mint_args: Json<MintArgs>,
+    mint_args: AuthorizedJson<MintArgs, MintScope>,
     state: &RocketState<State>,
+) -> Result<MintResponse, (Status, Json<ErrorResponse>)> {
+    mint_args
+        .target()
+        .map_err(|e| (Status::BadRequest, Json(ErrorResponse { error: e.to_string() })))?;
+
+    let mut mint_args = mint_args.into_inner();
+    mint_args.note_type.get_or_insert(state.note_type);
+    let asset = &mint_args.asset;
+    let registered = state
+        .store
+        .get_registered_asset(asset.origin_network, &asset.origin_address)
+        .await
+        .map_err(|e| (Status::InternalServerError, Json(ErrorResponse { error: e.to_string() })))?;
+
+    // Only registered assets are minted, with their canonical metadata
+    match registered {
+        Some(registered) if registered.enabled => mint_args.asset = registered.asset,
+        Some(registered) => {
+            return Err((
+                Status::Forbidden,
+                Json(ErrorResponse {
+                    error: format!("Asset {} is disabled", registered.asset.origin_address),
+                }),
+            ));
+        },
+        None => {
+            let error = OnchainError::AssetNotRegistered(
+                asset.origin_network,
+                asset.origin_address.clone(),
+            );
+            return Err((Status::UnprocessableEntity, Json(ErrorResponse { error: error.to_string() })));
+        },
+    }
+
+    let job = state.store.enqueue_mint_job(&mint_args).await.map_err(|e| {
+        (Status::InternalServerError, Json(ErrorResponse { error: e.to_string() }))
+    })?;
+
+    if let Some(minted_note) = job.minted_note() {
+        return Ok(MintResponse::Minted(Json(minted_note)));
+    }
+
+    // Held jobs are only minted once an admin approves them
+    if job.state == MintJobState::Held {
+        return Ok(MintResponse::Held(Json(job)));
+    }
+
+    dispatch_mint_job(state, &job).await.map(MintResponse::Minted)
+}
+
+/// Hands a persisted job to the worker of its asset and waits for the minted note.
+async fn dispatch_mint_job(
+    state: &State,
+    job: &MintJob,
 ) -> Result<Json<MintedNote>, (Status, Json<ErrorResponse>)> {
-    let recipient = Word::from(parse_hex_string_as_word(&mint_args.recipient)
-        .map_err(|e| (Status::BadRequest, Json(ErrorResponse { error: e.to_string() })))?);
+    // The job is persisted, the worker picks it up from the store once it is back or on its next
+    // sync, within `sync_interval_secs`
+    let worker = state.workers.for_asset(&job.asset);
+    if !worker.status.is_up() {
+        return Err((
+            Status::ServiceUnavailable,
+            Json(ErrorResponse {
+                error: format!(
+                    "Mint job {} is queued and will not be minted before its worker is back up",
+                    job.id
+                ),
+            }),
+        ));
+    }
+
     let (tx, rx) = tokio::sync::oneshot::channel();
 
-    let command = ClientCommand::MintNote {
-        recipient,
-        amount: mint_args.amount,
-        asset: mint_args.into_inner().asset,
-        tx,
-    };
+    let command = ClientCommand::MintNote { job_id: job.id, tx };
 
-    if let Err(e) = state.sender.try_send(command) {
-        return Err((Status::InternalServerError, Json(ErrorResponse { error: e.to_string() })));
+    if let Err(e) = worker.sender.try_send(command) {
+        return Err((
+            Status::ServiceUnavailable,
+            Json(ErrorResponse {
+                error: format!(
+                    "Mint job {} is queued and will not be minted before the next sync of its \
+                     worker: {}",
+                    job.id, e
+                ),
+            }),
+        ));
     }
 
     match rx.await {
         Ok(Ok(mint_result)) => Ok(Json(mint_result)),
         Ok(Err(e)) => {
-            warn!("{}, source: {}", e, e.source().unwrap().to_string());
+            match e.source() {
+                Some(source) => warn!("{}, source: {}", e, source),
+                None => warn!("{}", e),
+            }
             Err((Status::InternalServerError, Json(ErrorResponse { error: e.to_string() })))
         },
-        Err(e) => {
-            warn!("{}, source: {}", e, e.source().unwrap().to_string());
-            Err((Status::InternalServerError, Json(ErrorResponse { error: e.to_string() })))
+        Err(_) => {
+            warn!("Worker stopped while processing mint job {}", job.id);
+            Err((
+                Status::ServiceUnavailable,
+                Json(ErrorResponse {
+                    error: format!(
+                        "Mint job {} is queued and will not be minted before its worker is back \
+                         up",
+                        job.id
+                    ),
+                }),
+            ))
         },
     }
 }
 
+#[get("/mint/<id>")]
+async fn mint_job(
+    id: u64,
+    _auth: Authorized<ReadScope>,
+    state: &RocketState<State>,
+) -> Result<Json<MintJob>, (Status, Json<ErrorResponse>)> {
+    match state.store.get_mint_job(id).await {
+        Ok(Some(job)) => Ok(Json(job)),
+        Ok(None) => Err((
+            Status::NotFound,
+            Json(ErrorResponse { error: format!("Mint job {id} not found") }),
+        )),
+        Err(e) => Err((Status::InternalServerError, Json(ErrorResponse { error: e.to_string() }))),
+    }
+}
+
+/// Queues a held job and mints it like a regular request.
+#[post("/mint/<id>/approve")]
+async fn approve_mint_job(
+    id: u64,
+    _auth: Authorized<AdminScope>,
+    state: &RocketState<State>,
+) -> Result<Json<MintedNote>, (Status, Json<ErrorResponse>)> {
+    let approved = state
+        .store
+        .resolve_held_mint_job(id, MintJobState::Queued, None)
+        .await
+        .map_err(|e| (Status::InternalServerError, Json(ErrorResponse { error: e.to_string() })))?;
+    let job = held_mint_job_after(state, id, approved).await?;
+
+    info!("Mint job {id} approved");
+    dispatch_mint_job(state, &job).await
+}
+
+#[post("/mint/<id>/reject")]
+async fn reject_mint_job(
+    id: u64,
+    auth: Authorized<AdminScope>,
+    state: &RocketState<State>,
+) -> Result<Json<MintJob>, (Status, Json<ErrorResponse>)> {
+    let error = format!("Rejected by {}", auth.key.id);
+    let rejected = state
+        .store
+        .resolve_held_mint_job(id, MintJobState::Failed, Some(error))
+        .await
+        .map_err(|e| (Status::InternalServerError, Json(ErrorResponse { error: e.to_string() })))?;
+    let job = held_mint_job_after(state, id, rejected).await?;
+
+    info!("Mint job {id} rejected by {}", auth.key.id);
+    Ok(Json(job))
+}
+
+/// Reads a job after an attempt to resolve it, which fails if the job was not held.
+async fn held_mint_job_after(
+    state: &State,
+    id: u64,
+    resolved: bool,
+) -> Result<MintJob, (Status, Json<ErrorResponse>)> {
+    match state.store.get_mint_job(id).await {
+        Ok(Some(job)) if resolved => Ok(job),
+        Ok(Some(job)) => Err((
+            Status::Conflict,
+            Json(ErrorResponse { error: format!("Mint job {id} is {}, not held", job.state) }),
+        )),
+        Ok(None) => Err((
+            Status::NotFound,
+            Json(ErrorResponse { error: format!("Mint job {id} not found") }),
+        )),
+        Err(e) => Err((Status::InternalServerError, Json(ErrorResponse { error: e.to_string() }))),
+    }
+}
+
+#[get("/tx/<id>")]
+async fn transaction(
+    id: &str,
+    _auth: Authorized<ReadScope>,
+    state: &RocketState<State>,
+) -> Result<Json<TrackedTransaction>, (Status, Json<ErrorResponse>)> {
+    match state.store.get_tracked_transaction(id.to_lowercase()).await {
+        Ok(Some(transaction)) => Ok(Json(transaction)),
+        Ok(None) => Err((
+            Status::NotFound,
+            Json(ErrorResponse { error: format!("Transaction {id} not found") }),
+        )),
+        Err(e) => Err((Status::InternalServerError, Json(ErrorResponse { error: e.to_string() }))),
+    }
+}
+
 #[get("/chain-tip")]
-async fn chain_tip(state: &RocketState<State>) -> Result<String, Status> {
-    let (tx, rx) = tokio::sync::oneshot::channel();
-    state.sender.try_send(ClientCommand::GetChainTip(tx)).unwrap();
+async fn chain_tip(
+    _auth: Authorized<ReadScope>,
+    state: &RocketState<State>,
+) -> Result<String, Status> {
+    match state.sync_status.borrow().sync_height {
+        Some(sync_height) => Ok(sync_height.to_string()),
+        None => Err(Status::ServiceUnavailable),
+    }
+}
 
-    match rx.await {
-        Ok(Ok(block_number)) => Ok(block_number.to_string()),
-        Ok(Err(_)) | Err(_) => Err(Status::InternalServerError),
+/// Returns a page of the exit events matching the query, or, without `from`, `cursor` and
+/// filters, the events the calling API key did not acknowledge yet.
+///
+/// Every matching event is returned unless a `limit` or a `cursor` is given.
+#[get("/poll?<from>&<cursor>&<limit>&<filter..>")]
+async fn poll(
+    from: Option<u32>,
+    cursor: Option<u64>,
+    limit: Option<u32>,
+    filter: ExitFilter,
+    auth: Authorized<ReadScope>,
+    state: &RocketState<State>,
+) -> Result<Json<PolledEvents>, Status> {
+    // The exit events are scanned along with every sync, up to the sync height
+    let chain_tip = state.sync_status.borrow().sync_height.ok_or(Status::ServiceUnavailable)?;
+
+    let limit = match (limit, cursor) {
+        (None, None) => None,
+        (limit, _) => Some(limit.unwrap_or(DEFAULT_POLL_LIMIT).clamp(1, MAX_POLL_LIMIT)),
+    };
+
+    let page = if from.is_none() && cursor.is_none() && filter.is_empty() {
+        state.store.deliver_exit_events(auth.key.id, limit).await.map(|events| (events, None))
+    } else {
+        let query = ExitQuery { from_block: from, after: cursor, limit, filter };
+        state.store.query_exit_events(query).await.map(|page| {
+            let next_cursor = page
+                .last()
+                .filter(|_| limit.is_some_and(|limit| page.len() == limit as usize))
+                .map(|(id, _)| *id);
+            (page.into_iter().map(|(_, event)| event).collect(), next_cursor)
+        })
+    };
+
+    match page {
+        Ok((events, next_cursor)) => {
+            // The block of the last event of a full page may have more events on the next one
+            let chain_tip = match events.last() {
+                Some(last) if limit.is_some_and(|limit| events.len() == limit as usize) => {
+                    last.block_number.saturating_sub(1)
+                },
+                _ => chain_tip,
+            };
+            Ok(Json(PolledEvents { chain_tip, events, next_cursor }))
+        },
+        Err(_) => Err(Status::InternalServerError),
     }
 }
 
-#[get("/poll?<from>")]
-async fn poll(from: u32, state: &RocketState<State>) -> Result<Json<PolledEvents>, Status> {
-    let (tx, rx) = tokio::sync::oneshot::channel();
-    state.sender.try_send(ClientCommand::PollEvents{ tx, from_block: from }).unwrap();
+/// The position of the last exit event an SSE client received, sent back when it reconnects.
+struct LastEventId(Option<u64>);
 
-    match rx.await {
-        Ok(Ok(response)) => Ok(Json(response)),
-        Ok(Err(_)) | Err(_) => Err(Status::InternalServerError),
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for LastEventId {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let id = request.headers().get_one("Last-Event-ID").and_then(|id| id.parse().ok());
+        Outcome::Success(LastEventId(id))
+    }
+}
+
+/// Streams the exit events included from block `from` on, then the newly observed ones as
+/// they are scanned.
+///
+/// Every event carries its position as SSE id, so a reconnecting client resumes right after
+/// the last event it received.
+#[get("/poll/stream?<from>")]
+fn stream_exits(
+    from: Option<u32>,
+    last_event_id: LastEventId,
+    _auth: Authorized<ReadScope>,
+    state: &RocketState<State>,
+    mut shutdown: Shutdown,
+) -> EventStream![] {
+    // Subscribe before replaying, so nothing is lost between the replay and the live events
+    let mut live_events = state.exit_events.subscribe();
+    let store = state.store.clone();
+    let mut cursor = last_event_id.0;
+
+    EventStream! {
+        'stream: loop {
+            // Catch up from the store, on subscription or after lagging behind the scanner
+            loop {
+                let query = ExitQuery {
+                    from_block: from,
+                    after: cursor,
+                    limit: Some(MAX_POLL_LIMIT),
+                    ..Default::default()
+                };
+                let page = match store.query_exit_events(query).await {
+                    Ok(page) => page,
+                    Err(e) => {
+                        yield Event::data(e.to_string()).event("error");
+                        break 'stream;
+                    },
+                };
+                if page.is_empty() {
+                    break;
+                }
+                for (id, event) in page {
+                    cursor = Some(id);
+                    yield Event::json(&event).id(id.to_string());
+                }
+            }
+
+            loop {
+                let (id, event) = select! {
+                    received = live_events.recv() => match received {
+                        Ok(received) => received,
+                        Err(RecvError::Lagged(_)) => break,
+                        Err(RecvError::Closed) => break 'stream,
+                    },
+                    _ = &mut shutdown => break 'stream,
+                };
+                if cursor.is_some_and(|cursor| id <= cursor)
+                    || from.is_some_and(|from| event.block_number < from)
+                {
+                    continue;
+                }
+                cursor = Some(id);
+                yield Event::json(&event).id(id.to_string());
+            }
+        }
+    }
+}
+
+#[post("/poll/ack", format = "json", data = "<ack>")]
+async fn acknowledge_exits(
+    ack: AuthorizedJson<ExitAck, ReadScope>,
+    state: &RocketState<State>,
+) -> Result<Json<ExitCursor>, (Status, Json<ErrorResponse>)> {
+    let consumer = ack.key.id.clone();
+
+    match state.store.acknowledge_exit_events(consumer, ack.into_inner().note_ids).await {
+        Ok(cursor) => Ok(Json(cursor)),
+        Err(e) => Err((Status::InternalServerError, Json(ErrorResponse { error: e.to_string() }))),
+    }
+}
+
+#[get("/assets")]
+async fn registered_assets(
+    _auth: Authorized<ReadScope>,
+    state: &RocketState<State>,
+) -> Result<Json<Vec<RegisteredAsset>>, (Status, Json<ErrorResponse>)> {
+    match state.store.get_registered_assets().await {
+        Ok(assets) => Ok(Json(assets)),
+        Err(e) => Err((Status::InternalServerError, Json(ErrorResponse { error: e.to_string() }))),
+    }
+}
+
+/// Reconciles the minted, burned and on-chain supply of every registered asset.
+#[get("/supply")]
+async fn asset_supply(
+    _auth: Authorized<ReadScope>,
+    state: &RocketState<State>,
+) -> Result<Json<SupplyReport>, (Status, Json<ErrorResponse>)> {
+    match supply_report(&*state.client.rpc, &state.store).await {
+        Ok(report) => Ok(Json(report)),
+        Err(e) => Err((Status::InternalServerError, Json(ErrorResponse { error: e.to_string() }))),
+    }
+}
+
+#[post("/assets", format = "json", data = "<registration>")]
+async fn register_asset(
+    registration: AuthorizedJson<AssetRegistration, AdminScope>,
+    state: &RocketState<State>,
+) -> Result<Json<RegisteredAsset>, (Status, Json<ErrorResponse>)> {
+    let registration = registration.into_inner();
+    registration
+        .validate()
+        .map_err(|e| (Status::UnprocessableEntity, Json(ErrorResponse { error: e.to_string() })))?;
+
+    match state.store.register_asset(registration).await {
+        Ok(Some(asset)) => Ok(Json(asset)),
+        Ok(None) => Err((
+            Status::Conflict,
+            Json(ErrorResponse { error: "Asset is already registered".to_string() }),
+        )),
+        Err(e) => Err((Status::InternalServerError, Json(ErrorResponse { error: e.to_string() }))),
     }
 }
 
+async fn set_asset_enabled(
+    state: &State,
+    origin_network: u32,
+    origin_address: &str,
+    enabled: bool,
+) -> Result<Json<RegisteredAsset>, (Status, Json<ErrorResponse>)> {
+    match state.store.set_asset_enabled(origin_network, origin_address, enabled).await {
+        Ok(Some(asset)) => Ok(Json(asset)),
+        Ok(None) => {
+            let error = OnchainError::AssetNotRegistered(origin_network, origin_address.to_string());
+            Err((Status::NotFound, Json(ErrorResponse { error: error.to_string() })))
+        },
+        Err(e) => Err((Status::InternalServerError, Json(ErrorResponse { error: e.to_string() }))),
+    }
+}
+
+#[post("/assets/<origin_network>/<origin_address>/enable")]
+async fn enable_asset(
+    origin_network: u32,
+    origin_address: &str,
+    _auth: Authorized<AdminScope>,
+    state: &RocketState<State>,
+) -> Result<Json<RegisteredAsset>, (Status, Json<ErrorResponse>)> {
+    set_asset_enabled(state, origin_network, origin_address, true).await
+}
+
+/// Disabled assets keep their registry entry and faucet, their deposits are just not minted.
+#[post("/assets/<origin_network>/<origin_address>/disable")]
+async fn disable_asset(
+    origin_network: u32,
+    origin_address: &str,
+    _auth: Authorized<AdminScope>,
+    state: &RocketState<State>,
+) -> Result<Json<RegisteredAsset>, (Status, Json<ErrorResponse>)> {
+    set_asset_enabled(state, origin_network, origin_address, false).await
+}
+
+#[get("/assets/<origin_network>/<origin_address>/limits")]
+async fn mint_limits(
+    origin_network: u32,
+    origin_address: &str,
+    _auth: Authorized<ReadScope>,
+    state: &RocketState<State>,
+) -> Result<Json<MintLimits>, (Status, Json<ErrorResponse>)> {
+    match state.store.get_mint_limits(origin_network, origin_address).await {
+        Ok(limits) => Ok(Json(limits)),
+        Err(e) => Err((Status::InternalServerError, Json(ErrorResponse { error: e.to_string() }))),
+    }
+}
+
+/// Replaces the mint limits of a registered asset. Requests already held stay held.
+#[put("/assets/<origin_network>/<origin_address>/limits", format = "json", data = "<limits>")]
+async fn set_mint_limits(
+    origin_network: u32,
+    origin_address: &str,
+    limits: AuthorizedJson<MintLimits, AdminScope>,
+    state: &RocketState<State>,
+) -> Result<Json<MintLimits>, (Status, Json<ErrorResponse>)> {
+    let limits = limits.into_inner();
+    let registered = state
+        .store
+        .get_registered_asset(origin_network, origin_address)
+        .await
+        .map_err(|e| (Status::InternalServerError, Json(ErrorResponse { error: e.to_string() })))?;
+    let Some(registered) = registered else {
+        let error = OnchainError::AssetNotRegistered(origin_network, origin_address.to_string());
+        return Err((Status::NotFound, Json(ErrorResponse { error: error.to_string() })));
+    };
+
+    // Keyed by the canonical address, like the jobs the limits apply to
+    let asset = registered.asset;
+    match state.store.set_mint_limits(asset.origin_network, &asset.origin_address, limits).await {
+        Ok(()) => Ok(Json(limits)),
+        Err(e) => Err((Status::InternalServerError, Json(ErrorResponse { error: e.to_string() }))),
+    }
+}
+
+/// Liveness: answers as long as the server runs.
+#[get("/health")]
+fn liveness() -> &'static str {
+    "ok"
+}
+
+/// Readiness: the node answered the last sync, the store is writable, every worker up and the
+/// client store close enough to the chain tip.
+///
+/// The chain tip is the one read by the sync loop, so probes never call the node.
+#[get("/ready")]
+async fn readiness(state: &RocketState<State>) -> (Status, Json<Readiness>) {
+    let store_writable = match state.store.check_writable().await {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("Store is not writable: {}", e);
+            false
+        },
+    };
+    let SyncStatus { sync_height, chain_tip, .. } = *state.sync_status.borrow();
+
+    let readiness = Readiness::new(
+        chain_tip,
+        store_writable,
+        state.workers.is_up(),
+        sync_height,
+        state.max_sync_lag_blocks,
+    );
+    let status = if readiness.ready { Status::Ok } else { Status::ServiceUnavailable };
+
+    (status, Json(readiness))
+}
+
+/// Prometheus metrics, the gauges are refreshed on every scrape. The chain heights are the ones of
+/// the last sync, so scrapes never call the node.
+#[get("/metrics")]
+async fn prometheus_metrics(state: &RocketState<State>) -> Result<String, Status> {
+    let metrics = &state.metrics;
+
+    metrics.set_command_queue_depth(state.workers.queue_depth());
+    for job_state in [MintJobState::Queued, MintJobState::Held] {
+        match state.store.count_mint_jobs(job_state).await {
+            Ok(depth) => metrics.set_queue_depth(job_state, depth),
+            Err(e) => warn!("Mint job counts are unavailable: {}", e),
+        }
+    }
+    let SyncStatus { sync_height, chain_tip, .. } = *state.sync_status.borrow();
+    if let Some(sync_height) = sync_height {
+        metrics.set_sync_height(sync_height);
+    }
+    if let Some(chain_tip) = chain_tip {
+        metrics.set_chain_tip(chain_tip);
+    }
+    match state.store.get_asset_totals().await {
+        Ok(totals) => metrics.set_asset_totals(&totals),
+        Err(e) => warn!("Asset totals are unavailable: {}", e),
+    }
+
+    metrics.encode().map_err(|_| Status::InternalServerError)
+}
+
 struct State {
     client: Arc<OnchainClient>,
-    sender: Sender<ClientCommand>,
+    store: Arc<Store>,
+    workers: WorkerPool,
+    exit_events: ExitEventSender,
+    sync_status: SyncStatusReceiver,
+    metrics: Arc<Metrics>,
+    max_sync_lag_blocks: u32,
+    note_type: MintNoteType,
 }
 
-#[derive(Debug)]
-enum MintNoteError {}
-
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
 
+    let cli = Cli::parse();
+
     let rocket = rocket::build();
 
     let figment = rocket.figment();
-    let config: Config = figment.extract().expect("config");
+    let config: Arc<Config> = Arc::new(figment.extract()?);
+    config.validate()?;
+    info!("Using the `{}` profile, node at {}", figment.profile(), config.rpc_url());
 
-    let mut onchain: OnchainClient =
-        OnchainClient::new(config.rpc_url().clone(), config.rpc_timeout_ms().clone());
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(rocket, config).await,
+        Command::Faucet(command) => run_faucet_command(&config, command).await,
+        Command::Supply => run_supply_report(&config).await,
+    }
+}
+
+async fn serve(rocket: Rocket<Build>, config: Arc<Config>) -> Result<(), Box<dyn std::error::Error>> {
+    let authenticator = Authenticator::new(config.api_keys(), config.max_clock_skew_secs());
+    if !authenticator.has_keys() {
+        warn!("No API keys configured, all requests will be rejected");
+    }
+
+    let store = Arc::new(
+        Store::new(config.assets_store_path()).await.expect("Assets store to be initialized"),
+    );
+
+    let (exit_events, _) = tokio::sync::broadcast::channel(EXIT_STREAM_CAPACITY);
+
+    let (sync_status_sender, sync_status) = tokio::sync::watch::channel(SyncStatus::default());
 
-    let (sender, receiver) = tokio::sync::mpsc::channel(10);
+    let metrics = Arc::new(Metrics::new());
 
-    std::thread::spawn(move || {
-        let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
-        client_process_loop(&mut onchain, receiver, runtime);
-    });
+    let workers = WorkerPool::spawn(
+        store.clone(),
+        exit_events.clone(),
+        sync_status_sender,
+        config.clone(),
+        metrics.clone(),
+    );
 
     let onchain: OnchainClient =
         OnchainClient::new(config.rpc_url().clone(), config.rpc_timeout_ms().clone());
     rocket
-        .manage(State { client: Arc::new(onchain), sender })
-        .mount("/".to_string(), routes![chain_tip, mint_note, poll])
+        .manage(State {
+            client: Arc::new(onchain),
+            store,
+            workers,
+            exit_events,
+            sync_status,
+            metrics,
+            max_sync_lag_blocks: config.max_sync_lag_blocks(),
+            note_type: config.note_type(),
+        })
+        .manage(authenticator)
+        .mount("/".to_string(), routes![
+            chain_tip,
+            mint_note,
+            mint_job,
+            approve_mint_job,
+            reject_mint_job,
+            transaction,
+            poll,
+            stream_exits,
+            acknowledge_exits,
+            registered_assets,
+            register_asset,
+            enable_asset,
+            disable_asset,
+            mint_limits,
+            set_mint_limits,
+            asset_supply,
+            liveness,
+            readiness,
+            prometheus_metrics,
+        ])
         .launch()
         .await
         .unwrap();
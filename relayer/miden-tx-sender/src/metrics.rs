@@ -0,0 +1,199 @@
+use std::time::Duration;
+
+use prometheus::{
+    Encoder, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounterVec, IntGauge,
+    IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use crate::onchain::mint_job::MintJobState;
+
+use crate::onchain::prover::ProverKind;
+use crate::store::AssetTotals;
+
+/// Buckets of the mint and proof durations in seconds, proving alone takes seconds.
+const DURATION_BUCKETS: &[f64] = &[0.5, 1.0, 2.5, 5.0, 10.0, 20.0, 30.0, 60.0, 120.0];
+
+/// Prometheus metrics of the relayer.
+///
+/// The worker records the mints as they happen, the gauges are refreshed from the relayer state
+/// when the metrics are scraped.
+pub struct Metrics {
+    registry: Registry,
+    mints: IntCounterVec,
+    mint_duration: Histogram,
+    proof_duration: HistogramVec,
+    queue_depth: IntGaugeVec,
+    command_queue_depth: IntGauge,
+    sync_height: IntGauge,
+    chain_tip: IntGauge,
+    minted: GaugeVec,
+    burned: GaugeVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new_custom(Some("relayer".into()), None)
+            .expect("Metrics prefix to be valid");
+
+        let mints = IntCounterVec::new(
+            Opts::new("mints_total", "Processed mint jobs by outcome"),
+            &["outcome"],
+        )
+        .expect("Metric to be valid");
+        let mint_duration = Histogram::with_opts(
+            HistogramOpts::new("mint_duration_seconds", "Time to execute, prove and submit a mint")
+                .buckets(DURATION_BUCKETS.to_vec()),
+        )
+        .expect("Metric to be valid");
+        let proof_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "proof_duration_seconds",
+                "Time to prove and submit a mint transaction by prover",
+            )
+            .buckets(DURATION_BUCKETS.to_vec()),
+            &["prover"],
+        )
+        .expect("Metric to be valid");
+        let queue_depth = IntGaugeVec::new(
+            Opts::new("queue_depth", "Mint jobs waiting to be minted or approved, by state"),
+            &["state"],
+        )
+        .expect("Metric to be valid");
+        let command_queue_depth = IntGauge::new(
+            "command_queue_depth",
+            "Mint requests waiting in the command channels of the workers",
+        )
+        .expect("Metric to be valid");
+        let sync_height = IntGauge::new("sync_height", "Block the client store is synced to")
+            .expect("Metric to be valid");
+        let chain_tip =
+            IntGauge::new("chain_tip", "Latest block of the node").expect("Metric to be valid");
+        let asset_labels = ["origin_network", "origin_address", "symbol"];
+        let minted = GaugeVec::new(
+            Opts::new("asset_minted", "Amount minted per bridged asset, in base units"),
+            &asset_labels,
+        )
+        .expect("Metric to be valid");
+        let burned = GaugeVec::new(
+            Opts::new("asset_burned", "Amount burned by exits per bridged asset, in base units"),
+            &asset_labels,
+        )
+        .expect("Metric to be valid");
+
+        registry.register(Box::new(mints.clone())).expect("Metric to be registered once");
+        registry.register(Box::new(mint_duration.clone())).expect("Metric to be registered once");
+        registry.register(Box::new(proof_duration.clone())).expect("Metric to be registered once");
+        registry.register(Box::new(queue_depth.clone())).expect("Metric to be registered once");
+        registry
+            .register(Box::new(command_queue_depth.clone()))
+            .expect("Metric to be registered once");
+        registry.register(Box::new(sync_height.clone())).expect("Metric to be registered once");
+        registry.register(Box::new(chain_tip.clone())).expect("Metric to be registered once");
+        registry.register(Box::new(minted.clone())).expect("Metric to be registered once");
+        registry.register(Box::new(burned.clone())).expect("Metric to be registered once");
+
+        Metrics {
+            registry,
+            mints,
+            mint_duration,
+            proof_duration,
+            queue_depth,
+            command_queue_depth,
+            sync_height,
+            chain_tip,
+            minted,
+            burned,
+        }
+    }
+
+    pub fn observe_mint(&self, succeeded: bool, elapsed: Duration) {
+        let outcome = if succeeded { "submitted" } else { "failed" };
+        self.mints.with_label_values(&[outcome]).inc();
+        self.mint_duration.observe(elapsed.as_secs_f64());
+    }
+
+    /// Records the proof of a submitted transaction, the time of a failed remote proof included
+    /// when the local prover took over.
+    pub fn observe_proof(&self, prover: ProverKind, elapsed: Duration) {
+        self.proof_duration.with_label_values(&[prover.as_str()]).observe(elapsed.as_secs_f64());
+    }
+
+    /// Sets the number of persisted jobs in `state`, queued or held.
+    pub fn set_queue_depth(&self, state: MintJobState, depth: u64) {
+        self.queue_depth
+            .with_label_values(&[state.as_str()])
+            .set(i64::try_from(depth).unwrap_or(i64::MAX));
+    }
+
+    pub fn set_command_queue_depth(&self, depth: usize) {
+        self.command_queue_depth.set(i64::try_from(depth).unwrap_or(i64::MAX));
+    }
+
+    pub fn set_sync_height(&self, sync_height: u32) {
+        self.sync_height.set(i64::from(sync_height));
+    }
+
+    pub fn set_chain_tip(&self, chain_tip: u32) {
+        self.chain_tip.set(i64::from(chain_tip));
+    }
+
+    pub fn set_asset_totals(&self, totals: &[AssetTotals]) {
+        for totals in totals {
+            let network = totals.asset.origin_network.to_string();
+            let labels =
+                [network.as_str(), totals.asset.origin_address.as_str(), totals.asset.asset_symbol.as_str()];
+            self.minted.with_label_values(&labels).set(totals.minted as f64);
+            self.burned.with_label_values(&labels).set(totals.burned as f64);
+        }
+    }
+
+    /// Renders the metrics in the Prometheus text format.
+    pub fn encode(&self) -> Result<String, prometheus::Error> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+
+        Ok(String::from_utf8(buffer).expect("Prometheus text format to be UTF-8"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Metrics;
+    use crate::onchain::asset::Asset;
+    use crate::onchain::mint_job::MintJobState;
+    use crate::onchain::prover::ProverKind;
+    use crate::store::AssetTotals;
+
+    #[test]
+    fn should_render_recorded_metrics() {
+        let metrics = Metrics::new();
+        metrics.observe_mint(true, Duration::from_secs(3));
+        metrics.observe_mint(false, Duration::from_secs(1));
+        metrics.observe_proof(ProverKind::Remote, Duration::from_secs(2));
+        metrics.set_sync_height(10);
+        metrics.set_queue_depth(MintJobState::Held, 2);
+        metrics.set_asset_totals(&[AssetTotals {
+            asset: Asset {
+                origin_network: 0,
+                origin_address: "0x1c7d4b196cb0c7b01d743fbc6116a902379c7238".to_string(),
+                asset_symbol: "USDC".to_string(),
+                decimals: 6,
+            },
+            minted: 1000,
+            pending: 0,
+            burned: 300,
+        }]);
+
+        let rendered = metrics.encode().unwrap();
+        assert!(rendered.contains("relayer_mints_total{outcome=\"submitted\"} 1"));
+        assert!(rendered.contains("relayer_mint_duration_seconds_count 2"));
+        assert!(rendered.contains("relayer_proof_duration_seconds_count{prover=\"remote\"} 1"));
+        assert!(rendered.contains("relayer_sync_height 10"));
+        assert!(rendered.contains("relayer_queue_depth{state=\"held\"} 2"));
+        assert!(rendered.contains(
+            "relayer_asset_burned{origin_address=\"0x1c7d4b196cb0c7b01d743fbc6116a902379c7238\",origin_network=\"0\",symbol=\"USDC\"} 300"
+        ));
+    }
+}
@@ -1,10 +1,53 @@
+use miden_bridge::utils::evm_address_to_felts;
+use miden_lib::account::faucets::BasicFungibleFaucet;
+use miden_objects::Felt;
+use miden_objects::asset::{FungibleAsset, TokenSymbol};
 use rocket::serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize)]
+use crate::onchain::errors::OnchainError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(crate = "rocket::serde", rename_all = "camelCase")]
 pub struct Asset {
     pub origin_network: u32,
     pub origin_address: String,
     pub asset_symbol: String,
     pub decimals: u8,
-}
\ No newline at end of file
+}
+
+/// An asset an admin allows through the bridge, with the metadata of its wrapper faucet.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "camelCase")]
+pub struct AssetRegistration {
+    #[serde(flatten)]
+    pub asset: Asset,
+    /// Max supply of the wrapper faucet, in base units.
+    pub max_supply: u64,
+}
+
+impl AssetRegistration {
+    /// Checks that a wrapper faucet can be deployed for the asset.
+    pub fn validate(&self) -> Result<(), OnchainError> {
+        evm_address_to_felts(&self.asset.origin_address)?;
+
+        if self.max_supply > FungibleAsset::MAX_AMOUNT {
+            return Err(OnchainError::InvalidMaxSupply(self.max_supply));
+        }
+        let symbol = TokenSymbol::new(&self.asset.asset_symbol)?;
+        BasicFungibleFaucet::new(symbol, self.asset.decimals, Felt::new(self.max_supply))?;
+
+        Ok(())
+    }
+}
+
+/// A registry entry. Only the deposits of enabled assets are minted.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "camelCase")]
+pub struct RegisteredAsset {
+    #[serde(flatten)]
+    pub asset: Asset,
+    pub max_supply: u64,
+    pub enabled: bool,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
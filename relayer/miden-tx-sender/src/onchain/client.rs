@@ -1,7 +1,11 @@
 use std::collections::BTreeSet;
 use crate::onchain::deploy_token::insert_new_fungible_faucet;
+use crate::config::Config;
+use crate::metrics::Metrics;
 use crate::onchain::errors::OnchainError;
-use crate::onchain::mint_note::{mint_asset, MintedNote};
+use crate::onchain::mint_note::{
+    mint_assets, MintTarget, MintedNote, MINT_TRANSACTION_EXPIRATION_DELTA,
+};
 use crate::store::Store;
 use miden_client::block::BlockHeader;
 use miden_client::keystore::FilesystemKeyStore;
@@ -9,24 +13,35 @@ use miden_client::note::BlockNumber;
 use miden_client::rpc::{Endpoint, NodeRpcClient, TonicRpcClient};
 use miden_client::store::sqlite_store::SqliteStore;
 use miden_client::transaction::{
-    TransactionRequest, TransactionResult,
+    TransactionRequest, TransactionResult, TransactionStatus,
 };
-use miden_client::{Client, ExecutionOptions};
+use miden_client::{Client, ClientError, ExecutionOptions};
 use miden_objects::crypto::rand::RpoRandomCoin;
 use miden_objects::account::{AccountId, AccountStorageMode};
-use miden_objects::{Felt, Word, MAX_TX_EXECUTION_CYCLES, MIN_TX_EXECUTION_CYCLES};
+use miden_objects::note::NoteType;
+use miden_objects::{Felt, Word};
 use rand::rngs::StdRng;
 use rand::Rng;
 use std::sync::Arc;
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant};
 use miden_bridge::accounts::token_wrapper::bridge_note_tag;
 use miden_bridge::utils::evm_address_to_felts;
 use tokio::runtime;
 use tokio::runtime::Runtime;
+use tokio::select;
 use tokio::sync::mpsc::Receiver;
+use tokio::time::{self, MissedTickBehavior};
 use tokio::sync::oneshot::Sender as OneshotSender;
 use crate::onchain::asset::Asset;
-use crate::onchain::poll_events::{poll_events, PolledEvents};
+use crate::onchain::mint_job::{MintJob, MintJobState};
+use crate::onchain::prover::{connect_remote_prover, ProverKind, RemoteProver};
+use log::warn;
+use miden_client::store::{StoreError, TransactionFilter};
+use crate::onchain::supervisor::Worker;
+use crate::onchain::tracker::{
+    check_mint_on_node, settle_unknown_mint_transactions, NodeMintOutcome,
+};
+use crate::onchain::sync::{sync_client, sync_worker_client};
 
 pub struct OnchainClient {
     pub rpc: Arc<dyn NodeRpcClient + Send + Sync + 'static>,
@@ -59,7 +74,7 @@ impl OnchainClient {
         Ok(epoch_block_header)
     }
 
-    pub async fn get_chain_tip(&mut self) -> Result<BlockNumber, OnchainError> {
+    pub async fn get_chain_tip(&self) -> Result<BlockNumber, OnchainError> {
         let sync_response =
             self.rpc.sync_notes(0u32.into(), &BTreeSet::new()).await.map_err(OnchainError::RpcCallError)?;
 
@@ -76,146 +91,616 @@ pub async fn execute_tx(
 ) -> Result<TransactionResult, OnchainError> {
     let tx = execution_client.new_transaction(faucet_id, tx).await?;
 
-    execution_client.submit_transaction(tx.clone()).await?;
-
     Ok(tx)
 }
 
+/// Proves and submits a transaction, on the remote prover if one is configured. A transaction the
+/// remote prover fails to prove is proven locally instead.
+pub async fn submit_tx(
+    execution_client: &mut Client<FilesystemKeyStore<StdRng>>,
+    tx: TransactionResult,
+    remote_prover: Option<&RemoteProver>,
+) -> Result<ProverKind, OnchainError> {
+    if let Some(remote_prover) = remote_prover {
+        match execution_client.submit_transaction_with_prover(tx.clone(), remote_prover.clone()).await {
+            Ok(()) => return Ok(ProverKind::Remote),
+            // A transaction rejected by the node would be rejected again, only proving is retried
+            Err(ClientError::TransactionProvingError(e)) => {
+                warn!("Remote proving failed, proving locally: {}", e);
+            },
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    execution_client.submit_transaction(tx).await?;
+
+    Ok(ProverKind::Local)
+}
+
+/// Requests served by the worker. The chain state is read from the [`SyncStatus`] published by
+/// the periodic sync instead.
+///
+/// [`SyncStatus`]: crate::onchain::sync::SyncStatus
 pub enum ClientCommand {
-    GetChainTip(OneshotSender<Result<BlockNumber, OnchainError>>),
     MintNote {
-        recipient: Word,
-        amount: u64,
-        asset: Asset,
+        job_id: u64,
         tx: OneshotSender<Result<MintedNote, OnchainError>>,
     },
-    PollEvents {
-        from_block: u32,
-        tx: OneshotSender<Result<PolledEvents, OnchainError>>,
+}
+
+/// Returns the wrapper faucet of `asset`, deploying it with the registered metadata on the first
+/// mint.
+pub(crate) async fn get_or_deploy_faucet(
+    execution_client: &mut Client<FilesystemKeyStore<StdRng>>,
+    keystore: &FilesystemKeyStore<StdRng>,
+    assets_store: &Store,
+    asset: &Asset,
+) -> Result<AccountId, OnchainError> {
+    match assets_store.get_faucet_id(asset.origin_network, &asset.origin_address).await? {
+        Some(id) => {
+            // A faucet taken over from another worker after a change of the pool size is not in
+            // the client store of this one yet
+            if execution_client.get_account(id).await?.is_none() {
+                execution_client.import_account_by_id(id).await?;
+            }
+            Ok(id)
+        },
+        None => {
+            let registered = assets_store
+                .get_registered_asset(asset.origin_network, &asset.origin_address)
+                .await?
+                .ok_or_else(|| {
+                    OnchainError::AssetNotRegistered(
+                        asset.origin_network,
+                        asset.origin_address.clone(),
+                    )
+                })?;
+            let asset = &registered.asset;
+
+            let (account, _) = insert_new_fungible_faucet(
+                execution_client,
+                AccountStorageMode::Public,
+                &keystore,
+                &asset.asset_symbol,
+                asset.decimals,
+                registered.max_supply,
+                u64::from(asset.origin_network),
+                evm_address_to_felts(asset.origin_address.clone()).map_err(OnchainError::AddressFormatError)?
+            )
+            .await?;
+
+            let account_id = account.id();
+            assets_store
+                .add_faucet_id(asset.origin_network, &asset.origin_address, &account_id)
+                .await?;
+
+            Ok(account_id)
+        },
     }
 }
 
-async fn get_sync_height(execution_client: &mut Client<FilesystemKeyStore<StdRng>>) -> Result<BlockNumber, OnchainError> {
-    execution_client.sync_state().await?;
-    execution_client.get_sync_height().await.map_err(OnchainError::MidenClientError)
+/// A mint job left to mint, or its outcome if it needs no new transaction.
+enum LoadedMintJob {
+    Pending(MintJob, MintTarget),
+    Done(Result<MintedNote, OnchainError>),
 }
 
-async fn mint_note(
+/// Loads a persisted mint job to drive it to the submitted state.
+///
+/// Jobs with a recorded transaction, interrupted around its submission, are only minted again
+/// once it is known to never reach the chain. A transaction known to the client store and not
+/// discarded was submitted. The client store misses one the node accepted right before the
+/// relayer stopped, while the sync still pulls the minted faucet state from the node, so the node
+/// is asked for the job note: the job is minted again only once the transaction expired without
+/// its note on chain.
+async fn load_mint_job(
     execution_client: &mut Client<FilesystemKeyStore<StdRng>>,
-    keystore: &FilesystemKeyStore<StdRng>,
+    rpc: &(dyn NodeRpcClient + Send + Sync),
     assets_store: &Store,
-    recipient: Word,
-    amount: u64,
-    asset: Asset,
-) -> Result<MintedNote, OnchainError> {
-    let now = Instant::now();
-    execution_client.sync_state().await?;
+    job_id: u64,
+) -> Result<LoadedMintJob, OnchainError> {
+    let job =
+        assets_store.get_mint_job(job_id).await?.ok_or(OnchainError::MintJobNotFound(job_id))?;
 
-    let faucet_id =
-        match assets_store.get_faucet_id(asset.origin_network.clone(), &asset.origin_address.clone()).await? {
-            Some(id) => id,
-            None => {
-                let (account, _) = insert_new_fungible_faucet(
-                    execution_client,
-                    AccountStorageMode::Public,
-                    &keystore,
-                    &asset.asset_symbol,
-                    asset.decimals,
-                    u64::from(asset.origin_network),
-                    evm_address_to_felts(asset.origin_address.clone()).map_err(OnchainError::AddressFormatError)?
-                )
-                .await?;
+    if let Some(minted_note) = job.minted_note() {
+        return Ok(LoadedMintJob::Done(Ok(minted_note)));
+    }
+
+    match job.state {
+        MintJobState::Failed => {
+            let error = OnchainError::MintJobFailed(job.id, job.error.unwrap_or_default());
+            return Ok(LoadedMintJob::Done(Err(error)));
+        },
+        // Only an approval re-queues a held job
+        MintJobState::Held => {
+            let error = OnchainError::MintJobHeld(job.id, job.error.unwrap_or_default());
+            return Ok(LoadedMintJob::Done(Err(error)));
+        },
+        MintJobState::Queued | MintJobState::Executing => {
+            if let Some(transaction_id) = job.parsed_transaction_id()? {
+                let submitted = execution_client
+                    .get_transactions(TransactionFilter::Ids(vec![transaction_id]))
+                    .await?
+                    .iter()
+                    .any(|record| !matches!(record.status, TransactionStatus::Discarded(_)));
+
+                let outcome = if submitted {
+                    // The tracker settles it from the client store
+                    NodeMintOutcome::Unknown
+                } else {
+                    let sync_height = execution_client.get_sync_height().await?.as_u32();
+                    check_mint_on_node(rpc, &job, sync_height).await?
+                };
+                let committed_block = match outcome {
+                    NodeMintOutcome::Committed(block) => Some(block),
+                    NodeMintOutcome::Unknown => None,
+                    NodeMintOutcome::Absent => return pending_mint_job(assets_store, job).await,
+                };
 
-                let account_id = account.id();
                 assets_store
-                    .add_faucet_id(asset.origin_network.clone(), &asset.origin_address.clone(), &account_id)
+                    .recover_mint_transaction(transaction_id.to_hex(), committed_block)
                     .await?;
+                let job = assets_store
+                    .get_mint_job(job.id)
+                    .await?
+                    .ok_or(OnchainError::MintJobNotFound(job.id))?;
+
+                return Ok(LoadedMintJob::Done(job.minted_note().ok_or(
+                    OnchainError::MintJobFailed(
+                        job.id,
+                        "submitted job has no minted note recorded".to_string(),
+                    ),
+                )));
+            }
+        },
+        _ => {},
+    }
+
+    pending_mint_job(assets_store, job).await
+}
+
+/// Returns a job left to mint with its target, failing it if the target is invalid.
+async fn pending_mint_job(
+    assets_store: &Store,
+    job: MintJob,
+) -> Result<LoadedMintJob, OnchainError> {
+    match job.mint_target() {
+        Ok(target) => Ok(LoadedMintJob::Pending(job, target)),
+        Err(e) => {
+            assets_store.set_mint_job_state(job.id, MintJobState::Failed, Some(e.to_string())).await?;
+            Ok(LoadedMintJob::Done(Err(e)))
+        },
+    }
+}
+
+/// Mints the notes of a batch of jobs of the same asset with a single faucet transaction.
+async fn mint_notes(
+    execution_client: &mut Client<FilesystemKeyStore<StdRng>>,
+    keystore: &FilesystemKeyStore<StdRng>,
+    assets_store: &Store,
+    metrics: &Metrics,
+    remote_prover: Option<&RemoteProver>,
+    jobs: &[(MintJob, MintTarget)],
+) -> Result<Vec<MintedNote>, OnchainError> {
+    for (job, _) in jobs {
+        assets_store.set_mint_job_state(job.id, MintJobState::Executing, None).await?;
+    }
+
+    let faucet_id =
+        get_or_deploy_faucet(execution_client, keystore, assets_store, &jobs[0].0.asset).await?;
+
+    let mints: Vec<_> = jobs
+        .iter()
+        .map(|(job, target)| (*target, NoteType::from(job.note_type), job.amount))
+        .collect();
+    // The transaction references the sync height, its expiration is counted from there
+    let reference_block = execution_client.get_sync_height().await?.as_u32();
+    let mint_result = mint_assets(execution_client, faucet_id, &mints).await?;
+    let transaction_id = mint_result.executed_transaction().id();
+    let expiration_block = reference_block + u32::from(MINT_TRANSACTION_EXPIRATION_DELTA);
+
+    // The created notes are in the order of the jobs
+    let mut minted_notes: Vec<MintedNote> = jobs
+        .iter()
+        .zip(mint_result.created_notes().iter())
+        .map(|((job, _), note)| MintedNote {
+            job_id: job.id,
+            note_id: note.id().to_hex(),
+            faucet_id: faucet_id.to_hex(),
+            transaction_id: transaction_id.to_hex(),
+            prover: None,
+        })
+        .collect();
+
+    assets_store
+        .set_mint_batch_executed(
+            minted_notes.iter().map(|note| (note.job_id, note.note_id.clone())).collect(),
+            faucet_id.to_hex(),
+            transaction_id.to_hex(),
+            expiration_block,
+        )
+        .await?;
+    // The client proves the transaction as part of the submission
+    let proving_started_at = Instant::now();
+    let prover = match submit_tx(execution_client, mint_result, remote_prover).await {
+        Ok(prover) => {
+            metrics.observe_proof(prover, proving_started_at.elapsed());
+            Some(prover)
+        },
+        // Proving runs before the transaction is sent, the node never saw it
+        Err(e @ OnchainError::MidenClientError(ClientError::TransactionProvingError(_))) => {
+            return Err(e);
+        },
+        // The node may have accepted the transaction before the error, e.g. a timeout of the
+        // submission, so the jobs are submitted and settled from the node once it expires
+        Err(e) => {
+            warn!("Submission of transaction {} has no outcome: {}", transaction_id.to_hex(), e);
+            None
+        },
+    };
+    for note in &mut minted_notes {
+        note.prover = prover;
+    }
+
+    // The transaction may have reached the node, so the jobs must not fail from here on: a retry
+    // would mint them again. If the write fails they stay executing, and the next pass over the
+    // unfinished jobs finds their transaction in the client store or on the node.
+    let job_ids = minted_notes.iter().map(|note| note.job_id).collect();
+    if let Err(e) =
+        assets_store.set_mint_batch_submitted(job_ids, transaction_id.to_hex(), prover).await
+    {
+        warn!("Failed to record the submission of transaction {}: {}", transaction_id.to_hex(), e);
+    }
+
+    Ok(minted_notes)
+}
 
-                account_id
+/// Drives persisted mint jobs to the submitted state, returning the result of each job in the
+/// order of `job_ids`, which must not repeat a job.
+///
+/// The jobs are reloaded here, so a job submitted or failed since it was requested is answered
+/// from the store instead of being minted. The pending jobs of the same asset are minted
+/// together, by transactions of at most `max_batch_size` notes. A transaction that fails before
+/// reaching the node fails every job of its batch, one whose submission has no outcome leaves
+/// them submitted.
+async fn process_mint_jobs(
+    worker: &Worker,
+    execution_client: &mut Client<FilesystemKeyStore<StdRng>>,
+    keystore: &FilesystemKeyStore<StdRng>,
+    remote_prover: Option<&RemoteProver>,
+    job_ids: &[u64],
+) -> Vec<Result<MintedNote, OnchainError>> {
+    let assets_store = &*worker.store;
+    let metrics = &*worker.metrics;
+    let max_batch_size = worker.config.max_mint_batch_size();
+    let mut results: Vec<Option<Result<MintedNote, OnchainError>>> =
+        job_ids.iter().map(|_| None).collect();
+    // Pending jobs with their index in `job_ids`, grouped per asset in request order
+    let mut batches: Vec<Vec<(usize, (MintJob, MintTarget))>> = Vec::new();
+
+    for (index, job_id) in job_ids.iter().enumerate() {
+        match load_mint_job(execution_client, &*worker.client.rpc, assets_store, *job_id).await {
+            Ok(LoadedMintJob::Pending(job, target)) => {
+                let batch = batches.iter_mut().find(|batch| {
+                    let asset = &batch[0].1.0.asset;
+                    batch.len() < max_batch_size
+                        && asset.origin_network == job.asset.origin_network
+                        && asset.origin_address.eq_ignore_ascii_case(&job.asset.origin_address)
+                });
+                match batch {
+                    Some(batch) => batch.push((index, (job, target))),
+                    None => batches.push(vec![(index, (job, target))]),
+                }
             },
-        };
+            Ok(LoadedMintJob::Done(result)) => results[index] = Some(result),
+            Err(e) => results[index] = Some(Err(e)),
+        }
+    }
 
-    let mint_result = mint_asset(execution_client, faucet_id, recipient, amount).await?;
-    let note_id = mint_result.created_notes().get_note(0).id();
+    for batch in batches {
+        let (indexes, jobs): (Vec<usize>, Vec<(MintJob, MintTarget)>) = batch.into_iter().unzip();
+
+        let started_at = Instant::now();
+        let result =
+            mint_notes(execution_client, keystore, assets_store, metrics, remote_prover, &jobs)
+                .await;
+        let elapsed = started_at.elapsed();
+
+        match result {
+            Ok(minted_notes) => {
+                for (index, minted_note) in indexes.into_iter().zip(minted_notes) {
+                    metrics.observe_mint(true, elapsed);
+                    results[index] = Some(Ok(minted_note));
+                }
+            },
+            Err(e) => {
+                warn!("Mint batch of {} jobs failed: {}", jobs.len(), e);
+                let job_ids = jobs.iter().map(|(job, _)| job.id).collect();
+                let stored = assets_store.fail_unsent_mint_jobs(job_ids, e.to_string()).await;
+                for (index, (job, _)) in indexes.into_iter().zip(jobs) {
+                    metrics.observe_mint(false, elapsed);
+                    results[index] = Some(match &stored {
+                        Ok(()) => Err(OnchainError::MintJobFailed(job.id, e.to_string())),
+                        Err(store_error) => {
+                            Err(StoreError::DatabaseError(store_error.to_string()).into())
+                        },
+                    });
+                }
+            },
+        }
+    }
 
-    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards");
+    results.into_iter().map(|result| result.expect("Every mint job to have a result")).collect()
+}
 
-    println!("Minting took {}", now.elapsed().as_millis());
+/// Callers waiting for the result of a mint job.
+type MintReplies = Vec<OneshotSender<Result<MintedNote, OnchainError>>>;
+
+/// Adds the mint requests received within `wait` to `batch`, up to `max_size` jobs. Returns
+/// whether the command channel was closed meanwhile.
+///
+/// Concurrent requests for the same deposit send the same job, it is only added once and every
+/// caller gets its result.
+async fn collect_mint_batch(
+    receiver: &mut Receiver<ClientCommand>,
+    batch: &mut Vec<(u64, MintReplies)>,
+    max_size: usize,
+    wait: Duration,
+) -> bool {
+    let deadline = time::Instant::now() + wait;
+
+    while batch.len() < max_size {
+        // The timeout is only checked once the channel is empty, so the requests already waiting
+        // are batched even without a wait
+        match time::timeout_at(deadline, receiver.recv()).await {
+            Ok(Some(ClientCommand::MintNote { job_id, tx })) => {
+                match batch.iter_mut().find(|(id, _)| *id == job_id) {
+                    Some((_, replies)) => replies.push(tx),
+                    None => batch.push((job_id, vec![tx])),
+                }
+            },
+            Ok(None) => return true,
+            Err(_) => break,
+        }
+    }
 
-    Ok(MintedNote {
-        note_id: note_id.to_hex(),
-        faucet_id: faucet_id.to_hex(),
-        transaction_id: mint_result.executed_transaction().id().to_hex(),
-    })
+    false
 }
 
-pub fn client_process_loop(
-    mut client: &mut OnchainClient,
-    mut receiver: Receiver<ClientCommand>,
-    runtime: Runtime,
-) {
-    let miden_client_store =
-        Arc::new(runtime.block_on(SqliteStore::new("./miden_store.sql".into())).unwrap());
-    let assets_store = runtime
-        .block_on(Store::new("./assets_store.sql".into()))
-        .expect("Assets store to be initialized");
+/// Answers every caller waiting for a job. The error of a failed job is only kept for the first.
+fn reply_mint_result(job_id: u64, replies: MintReplies, result: Result<MintedNote, OnchainError>) {
+    let mut replies = replies.into_iter();
+    let Some(first) = replies.next() else {
+        return;
+    };
+
+    for tx in replies {
+        let copy = match &result {
+            Ok(minted_note) => Ok(minted_note.clone()),
+            Err(e) => Err(OnchainError::MintJobFailed(job_id, e.to_string())),
+        };
+        // The job is persisted, a caller that went away can still look it up
+        if tx.send(copy).is_err() {
+            warn!("Mint job {} finished after its request was dropped", job_id);
+        }
+    }
+    if first.send(result).is_err() {
+        warn!("Mint job {} finished after its request was dropped", job_id);
+    }
+}
+
+/// Drives the unfinished jobs of the assets the worker owns, the ones interrupted by a restart or
+/// never handed to it because its command channel was full, and settles its submitted jobs the
+/// client store does not know the transaction of.
+async fn resume_mint_jobs(
+    worker: &Worker,
+    execution_client: &mut Client<FilesystemKeyStore<StdRng>>,
+    keystore: &FilesystemKeyStore<StdRng>,
+    remote_prover: Option<&RemoteProver>,
+) -> Result<(), OnchainError> {
+    if let Err(e) = settle_unknown_mint_transactions(
+        execution_client,
+        &*worker.client.rpc,
+        &worker.store,
+        |job| worker.owns(&job.asset),
+    )
+    .await
+    {
+        warn!("Settling the transactions of worker {} failed: {}", worker.index, e);
+    }
+
+    let unfinished_jobs: Vec<u64> = worker
+        .store
+        .get_unfinished_mint_jobs()
+        .await?
+        .iter()
+        .filter(|job| worker.owns(&job.asset))
+        .map(|job| job.id)
+        .collect();
+    let results =
+        process_mint_jobs(worker, execution_client, keystore, remote_prover, &unfinished_jobs)
+            .await;
+    for (job_id, result) in unfinished_jobs.iter().zip(results) {
+        if let Err(e) = result {
+            warn!("Mint job {} recovery failed: {}", job_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Opens the miden client store of the worker `worker` and the keystore of the relayer.
+pub async fn build_client(
+    config: &Config,
+    rpc: Arc<dyn NodeRpcClient + Send + Sync + 'static>,
+    worker: usize,
+) -> Result<(Client<FilesystemKeyStore<StdRng>>, Arc<FilesystemKeyStore<StdRng>>), OnchainError> {
+    let miden_client_store = Arc::new(SqliteStore::new(config.miden_store_path(worker)).await?);
 
     let mut rng = rand::rng();
     let coin_seed: [u64; 4] = rng.random();
 
-    let keystore = Arc::new(FilesystemKeyStore::new("./keystore".into()).unwrap());
+    let keystore = Arc::new(FilesystemKeyStore::new(config.keystore_path())?);
 
     let rng = RpoRandomCoin::new(Word::from(coin_seed.map(Felt::new)));
-    let mut execution_client =
-        runtime.block_on(Client::new(
-            client.rpc.clone(),
-            Box::new(rng),
-            miden_client_store,
-            Some(keystore.clone()),
-            ExecutionOptions::new(
-                Some(MAX_TX_EXECUTION_CYCLES),
-                MIN_TX_EXECUTION_CYCLES,
-                false,
-                false,
-            ).unwrap(),
-            None,
-            None
-        )).unwrap();
+    let client = Client::new(
+        rpc,
+        Box::new(rng),
+        miden_client_store,
+        Some(keystore.clone()),
+        ExecutionOptions::new(
+            Some(config.max_tx_execution_cycles()),
+            config.expected_tx_execution_cycles(),
+            false,
+            false,
+        ).expect("Execution cycles to be validated with the config"),
+        None,
+        None
+    ).await?;
+
+    Ok((client, keystore))
+}
 
-    runtime.block_on(execution_client.add_note_tag(bridge_note_tag())).unwrap();
-    runtime.block_on(execution_client.sync_state()).unwrap();
+/// Runs the worker until the command channel is closed.
+///
+/// Every worker mints the jobs of the assets it owns and syncs its own client store, the first one
+/// also scans the exit events. After every sync the worker picks up the queued jobs of its assets
+/// from the store, so a job persisted while its command channel was full still gets minted.
+///
+/// Failures of a single command are answered to its caller, while failing to start the client
+/// or a panic ends the loop for the [supervisor](crate::onchain::supervisor) to restart it.
+pub fn client_process_loop(
+    worker: &Worker,
+    receiver: &mut Receiver<ClientCommand>,
+    runtime: Runtime,
+) -> Result<(), OnchainError> {
+    let (mut execution_client, keystore) =
+        runtime.block_on(build_client(&worker.config, worker.client.rpc.clone(), worker.index))?;
+
+    let remote_prover = worker.config.remote_prover_url().map(connect_remote_prover);
+
+    if worker.scans_exits() {
+        runtime.block_on(execution_client.add_note_tag(bridge_note_tag()))?;
+        runtime.block_on(sync_client(
+            &mut execution_client,
+            &worker.client,
+            &worker.store,
+            &worker.exit_events,
+            &worker.sync_status,
+        ))?;
+    } else {
+        runtime.block_on(sync_worker_client(&mut execution_client, &worker.store))?;
+    }
+
+    runtime.block_on(resume_mint_jobs(
+        worker,
+        &mut execution_client,
+        &keystore,
+        remote_prover.as_ref(),
+    ))?;
+
+    let sync_interval = worker.config.sync_interval();
+    let mut sync_ticker = runtime
+        .block_on(async { time::interval_at(time::Instant::now() + sync_interval, sync_interval) });
+    sync_ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    worker.status.set_up(true);
 
     loop {
-        let command = runtime.block_on(receiver.recv()).unwrap();
+        let command = runtime.block_on(async {
+            select! {
+                command = receiver.recv() => Some(command),
+                _ = sync_ticker.tick() => None,
+            }
+        });
+
+        let Some(command) = command else {
+            // Commands never sync, the client store is only refreshed here on schedule
+            if worker.scans_exits() {
+                if let Err(e) = runtime.block_on(sync_client(
+                    &mut execution_client,
+                    &worker.client,
+                    &worker.store,
+                    &worker.exit_events,
+                    &worker.sync_status,
+                )) {
+                    warn!("Client sync failed: {}", e);
+                }
+            } else if let Err(e) =
+                runtime.block_on(sync_worker_client(&mut execution_client, &worker.store))
+            {
+                warn!("Client sync of worker {} failed: {}", worker.index, e);
+            }
+            if let Err(e) = runtime.block_on(resume_mint_jobs(
+                worker,
+                &mut execution_client,
+                &keystore,
+                remote_prover.as_ref(),
+            )) {
+                warn!("Loading the queued mint jobs failed: {}", e);
+            }
+            continue;
+        };
+
+        // Every sender is gone, the server is shutting down
+        let Some(command) = command else {
+            return Ok(());
+        };
 
         match command {
-            ClientCommand::GetChainTip(sender) => {
-                let result = runtime.block_on(get_sync_height(&mut execution_client));
-                sender.send(result).unwrap();
-            },
-            ClientCommand::MintNote { recipient, amount, asset, tx } => {
-                let result = runtime.block_on(mint_note(
+            ClientCommand::MintNote { job_id, tx } => {
+                let mut batch = vec![(job_id, vec![tx])];
+                let closed = runtime.block_on(collect_mint_batch(
+                    receiver,
+                    &mut batch,
+                    worker.config.max_mint_batch_size(),
+                    worker.config.max_mint_batch_wait(),
+                ));
+
+                let job_ids: Vec<u64> = batch.iter().map(|(job_id, _)| *job_id).collect();
+                let results = runtime.block_on(process_mint_jobs(
+                    worker,
                     &mut execution_client,
                     &keystore,
-                    &assets_store,
-                    recipient,
-                    amount,
-                    asset,
+                    remote_prover.as_ref(),
+                    &job_ids,
                 ));
 
-                tx.send(result).unwrap();
+                for ((job_id, replies), result) in batch.into_iter().zip(results) {
+                    reply_mint_result(job_id, replies, result);
+                }
+
+                if closed {
+                    return Ok(());
+                }
             },
-            ClientCommand::PollEvents { from_block, tx } => {
+        }
+    }
+}
 
-                let result = runtime.block_on(
-                    poll_events(
-                        &mut execution_client,
-                        BlockNumber::from(from_block),
-                    )
-                );
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
 
-                tx.send(result).unwrap()
-            }
+    use tokio::sync::{mpsc, oneshot};
+
+    use super::{ClientCommand, collect_mint_batch};
+
+    #[tokio::test]
+    async fn should_batch_a_repeated_job_once() {
+        let (sender, mut receiver) = mpsc::channel(10);
+        let mut pending_replies = Vec::new();
+        for job_id in [1, 2, 1] {
+            let (tx, rx) = oneshot::channel();
+            sender.send(ClientCommand::MintNote { job_id, tx }).await.unwrap();
+            pending_replies.push(rx);
         }
+
+        let mut batch = Vec::new();
+        let closed = collect_mint_batch(&mut receiver, &mut batch, 10, Duration::ZERO).await;
+        assert!(!closed);
+        let jobs: Vec<(u64, usize)> =
+            batch.iter().map(|(job_id, replies)| (*job_id, replies.len())).collect();
+        assert_eq!(jobs, vec![(1, 2), (2, 1)]);
     }
 }
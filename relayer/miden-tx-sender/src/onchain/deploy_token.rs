@@ -5,19 +5,18 @@ use miden_client::keystore::FilesystemKeyStore;
 use miden_client::{Client, ClientError};
 use miden_lib::account::auth::{AuthRpoFalcon512Acl, AuthRpoFalcon512AclConfig};
 use miden_objects::account::{Account, AuthSecretKey};
-use miden_objects::asset::{FungibleAsset, TokenSymbol};
+use miden_objects::asset::TokenSymbol;
 use miden_objects::{Felt, Word, crypto::dsa::rpo_falcon512::SecretKey};
 use rand::prelude::StdRng;
 use rand::{rng, RngCore};
 
-const MAX_SUPPLY: Felt = Felt::new(FungibleAsset::MAX_AMOUNT);
-
 pub async fn insert_new_fungible_faucet(
     client: &mut Client<FilesystemKeyStore<StdRng>>,
     storage_mode: AccountStorageMode,
     keystore: &FilesystemKeyStore<StdRng>,
     symbol: &str,
     decimals: u8,
+    max_supply: u64,
     origin_network: u64,
     origin_address: [Felt; 3],
 ) -> Result<(Account, Word), ClientError> {
@@ -43,7 +42,7 @@ pub async fn insert_new_fungible_faucet(
             )?
         )
         .with_component(TokenWrapperAccount::new(origin_network, origin_address))
-        .with_component(BasicFungibleFaucet::new(symbol, decimals, MAX_SUPPLY).unwrap())
+        .with_component(BasicFungibleFaucet::new(symbol, decimals, Felt::new(max_supply)).unwrap())
         .build()?;
 
     client.add_account(&account, Some(seed), false).await?;
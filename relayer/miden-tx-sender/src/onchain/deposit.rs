@@ -0,0 +1,20 @@
+use rocket::serde::{Deserialize, Serialize};
+
+/// Identity of the EVM deposit a mint request originates from.
+///
+/// A deposit is uniquely identified by the chain it was made on, the hash of the transaction that
+/// emitted the bridge event and the bridge deposit count of that event.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "camelCase")]
+pub struct DepositId {
+    pub origin_network: u32,
+    pub tx_hash: String,
+    pub deposit_count: u64,
+}
+
+impl DepositId {
+    /// Returns the transaction hash in the canonical lowercase form used as a storage key.
+    pub fn normalized_tx_hash(&self) -> String {
+        self.tx_hash.to_lowercase()
+    }
+}
@@ -1,11 +1,16 @@
+use std::num::TryFromIntError;
+use miden_bridge::accounts::token_wrapper::TokenWrapperError;
+use miden_bridge::codec::CodecError;
 use miden_bridge::utils::AddressFormatError;
 use miden_client::ClientError;
+use miden_client::keystore::KeyStoreError;
 use miden_client::rpc::RpcError;
 use miden_client::store::StoreError;
 use miden_client::transaction::{TransactionProverError, TransactionRequestError};
 use miden_lib::account::faucets::FungibleFaucetError;
-use miden_objects::{AccountError, AssetError, NoteError, TokenSymbolError};
+use miden_objects::{AccountError, AccountIdError, AssetError, NoteError, TokenSymbolError};
 use miden_objects::account::AccountId;
+use miden_objects::utils::HexParseError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -27,11 +32,45 @@ pub enum OnchainError {
     #[error(transparent)]
     StoreError(#[from] StoreError),
     #[error(transparent)]
+    KeyStoreError(#[from] KeyStoreError),
+    #[error(transparent)]
     AddressFormatError(#[from] AddressFormatError),
     #[error(transparent)]
+    CodecError(#[from] CodecError),
+    #[error(transparent)]
+    TokenWrapperError(#[from] TokenWrapperError),
+    #[error(transparent)]
+    NumberOverflowError(#[from] TryFromIntError),
+    #[error(transparent)]
     TokenSymbolError(#[from] TokenSymbolError),
     #[error(transparent)]
     FungibleFaucetError(#[from] FungibleFaucetError),
-    #[error("Account with id {0} not found in storage")]
-    AccountNotFoundInStorage(AccountId),
+    #[error(transparent)]
+    HexParseError(#[from] HexParseError),
+    #[error(transparent)]
+    AccountIdError(#[from] AccountIdError),
+    #[error("Max supply {0} exceeds the fungible asset max amount")]
+    InvalidMaxSupply(u64),
+    #[error("Asset {1} of network {0} is not registered")]
+    AssetNotRegistered(u32, String),
+    #[error("Account {0} is not public on chain")]
+    AccountNotPublic(AccountId),
+    #[error("Faucet {0} does not run the code of token wrapper version {1}")]
+    OutdatedWrapperCode(AccountId, u32),
+    #[error("Account file of faucet {0} holds no key of its auth component")]
+    MissingAuthKey(AccountId),
+    #[error("Origin slot of faucet {0} does not match the asset")]
+    OriginMismatch(AccountId),
+    #[error("Symbol or decimals of faucet {0} differ from the registered asset")]
+    MetadataMismatch(AccountId),
+    #[error("Asset {1} of network {0} already has faucet {2}")]
+    FaucetAlreadyAssigned(u32, String, AccountId),
+    #[error("Exactly one of `recipient` and `accountId` must be set")]
+    InvalidMintTarget,
+    #[error("Mint job {0} not found")]
+    MintJobNotFound(u64),
+    #[error("Mint job {0} failed: {1}")]
+    MintJobFailed(u64, String),
+    #[error("Mint job {0} is held for approval: {1}")]
+    MintJobHeld(u64, String),
 }
@@ -0,0 +1,117 @@
+use miden_bridge::accounts::token_wrapper::{TokenWrapperAccount, TOKEN_WRAPPER_VERSION};
+use miden_bridge::utils::evm_address_to_felts;
+use miden_client::Client;
+use miden_client::keystore::FilesystemKeyStore;
+use miden_client::rpc::NodeRpcClient;
+use miden_objects::Word;
+use miden_objects::account::{AccountFile, AccountId, AuthSecretKey};
+use rand::rngs::StdRng;
+
+use crate::onchain::asset::{Asset, AssetRegistration};
+use crate::onchain::client::get_or_deploy_faucet;
+use crate::onchain::errors::OnchainError;
+use crate::store::Store;
+
+/// Creates the wrapper faucet of a registered asset ahead of its first mint, so its id is known
+/// in advance. The account is published on chain with its first mint transaction.
+///
+/// Returns the existing faucet if the asset already has one.
+pub async fn deploy_faucet(
+    execution_client: &mut Client<FilesystemKeyStore<StdRng>>,
+    keystore: &FilesystemKeyStore<StdRng>,
+    store: &Store,
+    origin_network: u32,
+    origin_address: &str,
+) -> Result<AccountId, OnchainError> {
+    let registered = store
+        .get_registered_asset(origin_network, origin_address)
+        .await?
+        .ok_or_else(|| OnchainError::AssetNotRegistered(origin_network, origin_address.to_string()))?;
+
+    get_or_deploy_faucet(execution_client, keystore, store, &registered.asset).await
+}
+
+/// Adopts a wrapper faucet deployed by another relayer host from its exported account file.
+///
+/// The on-chain state of the faucet is imported rather than the one of the file, after checking
+/// that it runs the current wrapper code, that the file holds its auth key and that its origin
+/// slot wraps the expected asset. The asset is registered with the metadata of the faucet if it is
+/// not yet.
+pub async fn import_faucet(
+    execution_client: &mut Client<FilesystemKeyStore<StdRng>>,
+    keystore: &FilesystemKeyStore<StdRng>,
+    rpc: &(dyn NodeRpcClient + Send + Sync),
+    store: &Store,
+    account_file: AccountFile,
+    origin_network: u32,
+    origin_address: &str,
+) -> Result<AccountId, OnchainError> {
+    let account_id = account_file.account.id();
+
+    let fetched = rpc.get_account_details(account_id).await?;
+    let account = fetched.account().ok_or(OnchainError::AccountNotPublic(account_id))?;
+
+    // Older wrappers cannot consume the CROSSCHAIN notes of this version, exits would fail
+    if !TokenWrapperAccount::has_current_code(account) {
+        return Err(OnchainError::OutdatedWrapperCode(account_id, TOKEN_WRAPPER_VERSION));
+    }
+
+    // The faucet only mints with a key matching its auth component
+    let auth_public_key = TokenWrapperAccount::auth_public_key(account)?;
+    if !account_file.auth_secret_keys.iter().any(|key| match key {
+        AuthSecretKey::RpoFalcon512(secret_key) => {
+            Word::from(secret_key.public_key()) == auth_public_key
+        },
+    }) {
+        return Err(OnchainError::MissingAuthKey(account_id));
+    }
+
+    let expected =
+        TokenWrapperAccount::new(u64::from(origin_network), evm_address_to_felts(origin_address)?);
+    if TokenWrapperAccount::origin_info(account)? != expected {
+        return Err(OnchainError::OriginMismatch(account_id));
+    }
+
+    match store.get_faucet_id(origin_network, origin_address).await? {
+        Some(faucet_id) if faucet_id == account_id => return Ok(account_id),
+        Some(faucet_id) => {
+            return Err(OnchainError::FaucetAlreadyAssigned(
+                origin_network,
+                origin_address.to_string(),
+                faucet_id,
+            ));
+        },
+        None => {},
+    }
+
+    let metadata = TokenWrapperAccount::token_metadata(account)?;
+    let registration = AssetRegistration {
+        asset: Asset {
+            origin_network,
+            origin_address: origin_address.to_string(),
+            asset_symbol: metadata.symbol().to_string()?,
+            decimals: metadata.decimals(),
+        },
+        max_supply: metadata.max_supply().as_int(),
+    };
+
+    // The registered metadata is the one recorded by the mints, it must be the faucet's
+    let registered = store.get_registered_asset(origin_network, origin_address).await?;
+    if registered.as_ref().is_some_and(|registered| {
+        registered.asset.asset_symbol != registration.asset.asset_symbol
+            || registered.asset.decimals != registration.asset.decimals
+    }) {
+        return Err(OnchainError::MetadataMismatch(account_id));
+    }
+
+    for key in &account_file.auth_secret_keys {
+        keystore.add_key(key)?;
+    }
+    execution_client.add_account(account, None, false).await?;
+    store.add_faucet_id(origin_network, origin_address, &account_id).await?;
+    if registered.is_none() {
+        store.register_asset(registration).await?;
+    }
+
+    Ok(account_id)
+}
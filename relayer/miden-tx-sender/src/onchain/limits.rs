@@ -0,0 +1,84 @@
+use std::fmt;
+
+use rocket::serde::{Deserialize, Serialize};
+
+/// Window of the rolling mint caps, in seconds.
+pub const MINT_LIMIT_WINDOW_SECS: u64 = 24 * 60 * 60;
+
+/// Mint limits of a registered asset, in base units. Unset limits are not enforced.
+///
+/// A request over a limit is held for an admin to approve or reject it rather than minted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "camelCase")]
+pub struct MintLimits {
+    #[serde(default)]
+    pub max_per_mint: Option<u64>,
+    /// Cap of the amount minted in the last 24 hours.
+    #[serde(default)]
+    pub daily_cap: Option<u64>,
+    /// Cap of the amount minted to a single recipient in the last 24 hours.
+    #[serde(default)]
+    pub recipient_daily_cap: Option<u64>,
+}
+
+/// Limit a mint request exceeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExceededLimit {
+    MaxPerMint(u64),
+    DailyCap(u64),
+    RecipientDailyCap(u64),
+}
+
+impl fmt::Display for ExceededLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExceededLimit::MaxPerMint(limit) => write!(f, "exceeds the max per mint of {limit}"),
+            ExceededLimit::DailyCap(limit) => write!(f, "exceeds the 24h cap of {limit}"),
+            ExceededLimit::RecipientDailyCap(limit) => {
+                write!(f, "exceeds the 24h cap of {limit} per recipient")
+            },
+        }
+    }
+}
+
+impl MintLimits {
+    /// Returns the first limit a mint of `amount` exceeds, given the amounts minted in the last
+    /// 24 hours for the asset and for the recipient of the mint.
+    pub fn exceeded(
+        &self,
+        amount: u64,
+        asset_minted: u64,
+        recipient_minted: u64,
+    ) -> Option<ExceededLimit> {
+        if let Some(limit) = self.max_per_mint.filter(|limit| amount > *limit) {
+            return Some(ExceededLimit::MaxPerMint(limit));
+        }
+        let asset_total = asset_minted.saturating_add(amount);
+        if let Some(limit) = self.daily_cap.filter(|limit| asset_total > *limit) {
+            return Some(ExceededLimit::DailyCap(limit));
+        }
+        self.recipient_daily_cap
+            .filter(|limit| recipient_minted.saturating_add(amount) > *limit)
+            .map(ExceededLimit::RecipientDailyCap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExceededLimit, MintLimits};
+
+    #[test]
+    fn should_check_every_limit() {
+        let limits = MintLimits {
+            max_per_mint: Some(1000),
+            daily_cap: Some(5000),
+            recipient_daily_cap: Some(2000),
+        };
+
+        assert_eq!(limits.exceeded(1000, 4000, 1000), None);
+        assert_eq!(limits.exceeded(1001, 0, 0), Some(ExceededLimit::MaxPerMint(1000)));
+        assert_eq!(limits.exceeded(1000, 4001, 0), Some(ExceededLimit::DailyCap(5000)));
+        assert_eq!(limits.exceeded(1000, 0, 1001), Some(ExceededLimit::RecipientDailyCap(2000)));
+        assert_eq!(MintLimits::default().exceeded(u64::MAX, u64::MAX, u64::MAX), None);
+    }
+}
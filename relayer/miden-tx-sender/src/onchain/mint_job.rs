@@ -0,0 +1,139 @@
+use std::fmt;
+use std::str::FromStr;
+
+use miden_client::transaction::TransactionId;
+use rocket::serde::{Deserialize, Serialize};
+
+use crate::onchain::asset::Asset;
+use crate::onchain::deposit::DepositId;
+use crate::onchain::errors::OnchainError;
+use crate::onchain::mint_note::{MintNoteType, MintTarget, MintedNote, RecipientKind};
+use crate::onchain::prover::ProverKind;
+use crate::onchain::tracker::parse_transaction_id;
+
+/// Lifecycle of a persisted mint request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum MintJobState {
+    /// Accepted and waiting for the worker.
+    Queued,
+    /// Exceeds a mint limit of its asset and waits for an admin to approve it. The exceeded limit
+    /// is stored in the job error.
+    Held,
+    /// Picked up by the worker. Once the transaction is executed its id is recorded before the
+    /// submission, so an interrupted job can be checked against the client store on recovery.
+    Executing,
+    /// The transaction was accepted by the node.
+    Submitted,
+    /// The transaction was included in a block.
+    Committed,
+    /// Execution or submission failed, or the submitted transaction was discarded. The reason is
+    /// stored in the job error.
+    Failed,
+}
+
+impl MintJobState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MintJobState::Queued => "queued",
+            MintJobState::Held => "held",
+            MintJobState::Executing => "executing",
+            MintJobState::Submitted => "submitted",
+            MintJobState::Committed => "committed",
+            MintJobState::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for MintJobState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MintJobState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(MintJobState::Queued),
+            "held" => Ok(MintJobState::Held),
+            "executing" => Ok(MintJobState::Executing),
+            "submitted" => Ok(MintJobState::Submitted),
+            "committed" => Ok(MintJobState::Committed),
+            "failed" => Ok(MintJobState::Failed),
+            other => Err(format!("Unknown mint job state: `{other}`")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "camelCase")]
+pub struct MintJob {
+    pub id: u64,
+    pub deposit: DepositId,
+    pub asset: Asset,
+    pub amount: u64,
+    /// Recipient digest, or account id of a P2ID note.
+    pub recipient: String,
+    pub recipient_kind: RecipientKind,
+    pub note_type: MintNoteType,
+    pub state: MintJobState,
+    pub faucet_id: Option<String>,
+    pub note_id: Option<String>,
+    pub transaction_id: Option<String>,
+    /// Last block the job transaction can be included in.
+    pub expiration_block: Option<u32>,
+    pub error: Option<String>,
+    /// Block the job transaction was included in, set by the transaction tracker.
+    pub committed_block: Option<u32>,
+    pub prover: Option<ProverKind>,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl MintJob {
+    /// Returns the minted note once the job transaction has been submitted.
+    pub fn minted_note(&self) -> Option<MintedNote> {
+        match self.state {
+            MintJobState::Submitted | MintJobState::Committed => Some(MintedNote {
+                job_id: self.id,
+                note_id: self.note_id.clone()?,
+                faucet_id: self.faucet_id.clone()?,
+                transaction_id: self.transaction_id.clone()?,
+                prover: self.prover,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn mint_target(&self) -> Result<MintTarget, OnchainError> {
+        MintTarget::parse(self.recipient_kind, &self.recipient)
+    }
+
+    pub fn parsed_transaction_id(&self) -> Result<Option<TransactionId>, OnchainError> {
+        self.transaction_id.as_deref().map(parse_transaction_id).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::MintJobState;
+
+    #[test]
+    fn should_roundtrip_state_names() {
+        for state in [
+            MintJobState::Queued,
+            MintJobState::Held,
+            MintJobState::Executing,
+            MintJobState::Submitted,
+            MintJobState::Committed,
+            MintJobState::Failed,
+        ] {
+            assert_eq!(MintJobState::from_str(state.as_str()), Ok(state));
+        }
+        assert!(MintJobState::from_str("unknown").is_err());
+    }
+}
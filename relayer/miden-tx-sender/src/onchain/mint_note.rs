@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use miden_bridge::notes::BRIDGE_USECASE;
 use crate::onchain::client::execute_tx;
 use crate::onchain::errors::OnchainError;
@@ -11,54 +13,233 @@ use miden_objects::asset::{Asset as MidenAsset, FungibleAsset};
 use miden_objects::note::{
     NoteAssets, NoteExecutionHint, NoteMetadata, NoteTag, NoteType, PartialNote,
 };
+use miden_lib::note::create_p2id_note;
+use miden_objects::crypto::rand::RpoRandomCoin;
 use miden_objects::transaction::OutputNote;
+use miden_objects::utils::parse_hex_string_as_word;
 use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
 use crate::onchain::asset::Asset;
+use crate::onchain::deposit::DepositId;
+use crate::onchain::prover::ProverKind;
+
+
+/// Type of the notes minted to the depositors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum MintNoteType {
+    /// Only the note commitment is published, its owner knows the details offline.
+    #[default]
+    Private,
+    /// The note details are published, its owner receives it through the regular sync.
+    Public,
+}
+
+impl MintNoteType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MintNoteType::Private => "private",
+            MintNoteType::Public => "public",
+        }
+    }
+}
+
+impl FromStr for MintNoteType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "private" => Ok(MintNoteType::Private),
+            "public" => Ok(MintNoteType::Public),
+            other => Err(format!("Unknown note type: `{other}`")),
+        }
+    }
+}
+
+impl From<MintNoteType> for NoteType {
+    fn from(note_type: MintNoteType) -> Self {
+        match note_type {
+            MintNoteType::Private => NoteType::Private,
+            MintNoteType::Public => NoteType::Public,
+        }
+    }
+}
+
+/// How the owner of a minted note is given, stored with the job next to the recipient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum RecipientKind {
+    /// Recipient digest of a note built by the depositor.
+    Digest,
+    /// Account id receiving a P2ID note.
+    Account,
+}
+
+impl RecipientKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecipientKind::Digest => "digest",
+            RecipientKind::Account => "account",
+        }
+    }
+}
+
+impl FromStr for RecipientKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "digest" => Ok(RecipientKind::Digest),
+            "account" => Ok(RecipientKind::Account),
+            other => Err(format!("Unknown recipient kind: `{other}`")),
+        }
+    }
+}
+
+/// Destination of a minted note.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MintTarget {
+    Recipient(Word),
+    Account(AccountId),
+}
 
+impl MintTarget {
+    /// Parses a recipient as stored with its job.
+    pub fn parse(kind: RecipientKind, recipient: &str) -> Result<Self, OnchainError> {
+        match kind {
+            RecipientKind::Digest => {
+                Ok(MintTarget::Recipient(Word::from(parse_hex_string_as_word(recipient)?)))
+            },
+            RecipientKind::Account => Ok(MintTarget::Account(parse_account_id(recipient)?)),
+        }
+    }
+}
+
+/// Parses an account id either as hex or as bech32.
+pub fn parse_account_id(account_id: &str) -> Result<AccountId, OnchainError> {
+    if account_id.starts_with("0x") {
+        Ok(AccountId::from_hex(account_id)?)
+    } else {
+        Ok(AccountId::from_bech32(account_id)?.1)
+    }
+}
 
+/// A mint request. Exactly one of `recipient` and `account_id` is set.
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(crate = "rocket::serde", rename_all = "camelCase")]
 pub struct MintArgs {
+    pub deposit: DepositId,
     pub asset: Asset,
     pub amount: u64,
-    pub recipient: String,
+    /// Recipient digest of a note built by the depositor.
+    #[serde(default)]
+    pub recipient: Option<String>,
+    /// Account receiving a P2ID note.
+    #[serde(default)]
+    pub account_id: Option<String>,
+    /// Type of the minted note, the configured one if unset.
+    #[serde(default)]
+    pub note_type: Option<MintNoteType>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+impl MintArgs {
+    pub fn recipient_kind(&self) -> RecipientKind {
+        if self.account_id.is_some() { RecipientKind::Account } else { RecipientKind::Digest }
+    }
+
+    /// Returns the recipient digest or the account id, as stored with the job.
+    pub fn note_recipient(&self) -> Option<&str> {
+        self.account_id.as_deref().or(self.recipient.as_deref())
+    }
+
+    pub fn target(&self) -> Result<MintTarget, OnchainError> {
+        match (&self.recipient, &self.account_id) {
+            (Some(recipient), None) => MintTarget::parse(RecipientKind::Digest, recipient),
+            (None, Some(account_id)) => MintTarget::parse(RecipientKind::Account, account_id),
+            _ => Err(OnchainError::InvalidMintTarget),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(crate = "rocket::serde", rename_all = "camelCase")]
 pub struct MintedNote {
+    pub job_id: u64,
     pub note_id: String,
     pub faucet_id: String,
     pub transaction_id: String,
+    /// Prover of the transaction, unknown for a job recovered after a restart.
+    pub prover: Option<ProverKind>,
 }
 
-pub async fn mint_asset(
-    client: &mut Client<FilesystemKeyStore<StdRng>>,
+/// Builds the output note minting `amount` of the faucet asset to `target`.
+fn mint_output_note(
     faucet_id: AccountId,
-    recipient: Word,
+    target: MintTarget,
+    note_type: NoteType,
     amount: u64,
-) -> Result<TransactionResult, OnchainError> {
+) -> Result<OutputNote, OnchainError> {
     let asset =
         MidenAsset::Fungible(FungibleAsset::new(faucet_id, amount).map_err(OnchainError::AssetError)?);
 
-    let assets = NoteAssets::new(vec![asset]).map_err(OnchainError::NoteError)?;
+    match target {
+        MintTarget::Recipient(recipient) => {
+            let assets = NoteAssets::new(vec![asset]).map_err(OnchainError::NoteError)?;
 
-    let tx_request = TransactionRequestBuilder::new()
-        .own_output_notes(vec![OutputNote::Partial(PartialNote::new(
-            NoteMetadata::new(
+            Ok(OutputNote::Partial(PartialNote::new(
+                NoteMetadata::new(
+                    faucet_id,
+                    note_type,
+                    NoteTag::for_local_use_case(BRIDGE_USECASE, 0).map_err(OnchainError::NoteError)?,
+                    NoteExecutionHint::Always,
+                    Felt::new(0),
+                )
+                .map_err(OnchainError::NoteError)?,
+                recipient.into(),
+                assets,
+            )))
+        },
+        MintTarget::Account(account_id) => {
+            let seed: [u64; 4] = rand::random();
+            let mut rng = RpoRandomCoin::new(Word::from(seed.map(Felt::new)));
+
+            // The relayer knows the details of a P2ID note, so the full note is built here
+            Ok(OutputNote::Full(create_p2id_note(
                 faucet_id,
-                NoteType::Private,
-                NoteTag::for_local_use_case(BRIDGE_USECASE, 0).map_err(OnchainError::NoteError)?,
-                NoteExecutionHint::Always,
+                account_id,
+                vec![asset],
+                note_type,
                 Felt::new(0),
-            )
-            .map_err(OnchainError::NoteError)?,
-            recipient.into(),
-            assets,
-        ))])
+                &mut rng,
+            )?))
+        },
+    }
+}
+
+/// Blocks after its reference block a mint transaction can be included in. Past them the node
+/// drops it, so a job whose note is still not on chain can be minted again.
+pub const MINT_TRANSACTION_EXPIRATION_DELTA: u16 = 20;
+
+/// Executes a single faucet transaction minting one note per `(target, note_type, amount)`. The
+/// created notes are in the order of `mints`.
+pub async fn mint_assets(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    faucet_id: AccountId,
+    mints: &[(MintTarget, NoteType, u64)],
+) -> Result<TransactionResult, OnchainError> {
+    let output_notes = mints
+        .iter()
+        .map(|(target, note_type, amount)| {
+            mint_output_note(faucet_id, *target, *note_type, *amount)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let tx_request = TransactionRequestBuilder::new()
+        .own_output_notes(output_notes)
+        .expiration_delta(MINT_TRANSACTION_EXPIRATION_DELTA)
         .build()?;
 
+    // The transaction is only executed here, the caller records its id before submitting it
     let transaction =
         execute_tx(client, tx_request, faucet_id).await.map_err(OnchainError::from)?;
 
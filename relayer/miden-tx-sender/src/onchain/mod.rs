@@ -1,10 +1,19 @@
 pub mod client;
 mod deploy_token;
-mod errors;
+pub mod faucets;
+pub mod errors;
+pub mod mint_job;
 pub mod mint_note;
 mod responses;
 pub mod poll_events;
-mod asset;
+pub mod prover;
+pub mod tracker;
+pub mod supervisor;
+pub mod supply;
+pub mod sync;
+pub mod asset;
+pub mod deposit;
+pub mod limits;
 
 pub use client::OnchainClient;
 pub use responses::*;
@@ -1,19 +1,31 @@
-use std::collections::HashMap;
-use miden_bridge::accounts::token_wrapper::bridge_note_tag;
+use std::collections::{BTreeSet, HashMap};
+use miden_bridge::accounts::token_wrapper::{bridge_note_tag, TokenWrapperAccount};
+use miden_bridge::codec::BridgeEventInputs;
 use miden_bridge::utils::felts_to_evm_addresses;
 use miden_client::Client;
 use miden_client::keystore::FilesystemKeyStore;
+use miden_client::rpc::NodeRpcClient;
 use miden_client::store::{InputNoteRecord, NoteFilter};
-use miden_objects::block::BlockNumber;
+use miden_objects::account::{Account, AccountId};
+use miden_objects::note::NoteId;
 use miden_objects::utils::ToHex;
+use log::warn;
 use rand::rngs::StdRng;
+use rocket::FromForm;
 use rocket::serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 use crate::onchain::asset::Asset;
 use crate::onchain::errors::OnchainError;
-use crate::utils::metadata::decode_slot_into_token_metadata;
-use crate::utils::origin::decode_slot_into_origin_info;
+use crate::store::Store;
 
-#[derive(Debug, Deserialize, Serialize)]
+/// How many exit events a stream subscriber can lag behind before it has to catch up from the
+/// store.
+pub const EXIT_STREAM_CAPACITY: usize = 1024;
+
+/// Publishes the newly persisted exit events, with their position, to the stream subscribers.
+pub type ExitEventSender = broadcast::Sender<(u64, ExitEvent)>;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(crate = "rocket::serde", rename_all = "camelCase")]
 pub struct ExitEvent {
     pub note_id: String,
@@ -29,80 +41,263 @@ pub struct ExitEvent {
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(crate = "rocket::serde", rename_all = "camelCase")]
 pub struct PolledEvents {
+    /// Block the events are complete up to: the sync height, or the block before the last event
+    /// of a page cut short by its limit
     pub chain_tip: u32,
-    pub events: Vec<ExitEvent>
+    pub events: Vec<ExitEvent>,
+    /// Cursor of the next page, set when the page is full
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<u64>,
+}
+
+/// Page size of `/poll` when a cursor is given without a limit.
+pub const DEFAULT_POLL_LIMIT: u32 = 100;
+/// Largest page size of `/poll`.
+pub const MAX_POLL_LIMIT: u32 = 1000;
+
+/// Optional filters of the exit event query.
+#[derive(Debug, Clone, Default, FromForm)]
+pub struct ExitFilter {
+    #[field(name = "destinationChain")]
+    pub destination_chain: Option<u64>,
+    #[field(name = "originNetwork")]
+    pub origin_network: Option<u32>,
+    #[field(name = "originAddress")]
+    pub origin_address: Option<String>,
+    pub receiver: Option<String>,
+    #[field(name = "noteId")]
+    pub note_id: Option<String>,
 }
 
+impl ExitFilter {
+    pub fn is_empty(&self) -> bool {
+        self.destination_chain.is_none()
+            && self.origin_network.is_none()
+            && self.origin_address.is_none()
+            && self.receiver.is_none()
+            && self.note_id.is_none()
+    }
+}
+
+/// Page of the exit events matching `filter`, committed at or after `from_block` and stored
+/// after the `after` cursor. Every matching event is returned without `limit`.
+#[derive(Debug, Clone, Default)]
+pub struct ExitQuery {
+    pub from_block: Option<u32>,
+    pub after: Option<u64>,
+    pub limit: Option<u32>,
+    pub filter: ExitFilter,
+}
+
+/// Delivery state of an exit event for a single consumer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitDeliveryStatus {
+    /// Returned by `/poll` but not acknowledged yet, so it is returned again.
+    Delivered,
+    /// Acknowledged by the consumer, never returned again.
+    Acknowledged,
+}
+
+impl ExitDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitDeliveryStatus::Delivered => "delivered",
+            ExitDeliveryStatus::Acknowledged => "acknowledged",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "camelCase")]
+pub struct ExitAck {
+    pub note_ids: Vec<String>,
+}
+
+/// Position of a consumer in the exit event stream: every event up to `position` is
+/// acknowledged.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "camelCase")]
+pub struct ExitCursor {
+    pub consumer: String,
+    pub position: u64,
+    /// Block of the event at `position`
+    pub block_number: Option<u32>,
+    /// Number of events acknowledged by the request
+    pub acknowledged: usize,
+}
+
+/// Bridge note whose sender could not be resolved when its block was scanned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnresolvedExitNote {
+    pub note_id: NoteId,
+    pub block_number: u32,
+    pub sender: AccountId,
+}
+
+/// How long an unresolved bridge note is retried before it is dropped, in seconds.
+pub const UNRESOLVED_EXIT_NOTE_RETENTION_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Scans the bridge notes committed since the last scan into the exit events of `store`.
+///
+/// The node lists the bridge notes of the blocks after the scan cursor, so only those notes are
+/// read from the client store. A note whose sender is not resolved yet is recorded and retried on
+/// the next scans, for [`UNRESOLVED_EXIT_NOTE_RETENTION_SECS`], instead of holding back the
+/// cursor. Its event keeps the block of the note but gets the position of its resolution, so
+/// consumers follow the positions rather than the blocks.
+///
+/// Returns the block the persisted exit events are complete up to. Only reads the state of the
+/// last sync, the caller is responsible for syncing the client.
 pub async fn poll_events(
     storage_client: &mut Client<FilesystemKeyStore<StdRng>>,
-    from: BlockNumber
-) -> Result<PolledEvents, OnchainError> {
-    storage_client.sync_state().await.map_err(OnchainError::from)?;
-
-    let notes = storage_client.get_input_notes(NoteFilter::Committed)
-        .await.map_err(OnchainError::from)?;
-
-    let notes: Vec<(&InputNoteRecord, BlockNumber)> = notes.iter().filter(|n| n.metadata().unwrap().tag() == bridge_note_tag() &&
-        n.inclusion_proof().unwrap().location().block_num().as_u64() >= from.as_u64())
-            .map(|n| (n, n.inclusion_proof().unwrap().location().block_num())).collect();
-
-    let mut whitelisted_notes = Vec::new();
-
-    let mut tokens = HashMap::new();
-
-    for (note, block) in notes.clone() {
-        let sender = note.metadata().unwrap().sender();
-        let account = storage_client.get_account(
-            sender.clone()
-        )
-            .await.map_err(OnchainError::from)?
-            .ok_or(OnchainError::AccountNotFoundInStorage(sender.clone()));
-        if let Ok(account) = account {
-            tokens.insert(sender.to_hex(), account.account().clone());
-            whitelisted_notes.push((note.clone(), block))
+    rpc: &(dyn NodeRpcClient + Send + Sync),
+    store: &Store,
+    exit_events: &ExitEventSender,
+) -> Result<u32, OnchainError> {
+    let sync_height = storage_client.get_sync_height().await.map_err(OnchainError::from)?.as_u32();
+
+    for note in store.prune_unresolved_exit_notes(UNRESOLVED_EXIT_NOTE_RETENTION_SECS).await? {
+        warn!("Dropped unresolved bridge note {} of block {}", note.note_id, note.block_number);
+    }
+    let mut notes = store.get_resolvable_exit_notes().await?;
+
+    let tags = BTreeSet::from([bridge_note_tag()]);
+    let mut scanned_block = store.get_exit_scan_block().await?.unwrap_or(0);
+    while scanned_block < sync_height {
+        // Each response holds the notes of the next block with a bridge note, or of the chain tip
+        let info = rpc
+            .sync_notes(scanned_block.into(), &tags)
+            .await
+            .map_err(OnchainError::RpcCallError)?;
+        let block_number = info.block_header.block_num().as_u32();
+
+        // The notes past the sync height are not in the client store yet
+        if info.notes.is_empty() || block_number > sync_height {
+            scanned_block = sync_height;
+            break;
+        }
+        if block_number <= scanned_block {
+            warn!("Node returned the bridge notes of block {} again", block_number);
+            break;
         }
+
+        notes.extend(info.notes.iter().map(|note| UnresolvedExitNote {
+            note_id: *note.note_id(),
+            block_number,
+            sender: note.metadata().sender(),
+        }));
+        scanned_block = block_number;
     }
 
-    let chain_tip = storage_client.get_sync_height().await.map_err(OnchainError::from)?.as_u32();
-
-    Ok(PolledEvents {
-        chain_tip,
-        events: whitelisted_notes.iter().map(|(event, block_number)| {
-            let sender = event.metadata().unwrap().sender();
-            let token_account = tokens.get(&sender.clone().to_hex())
-                .unwrap().clone();
-
-            let origin_slot = token_account.storage().slots().get(4).unwrap();
-            let metadata_slot = token_account.storage().slots().get(5).unwrap();
-            let (origin_network, origin_address) = decode_slot_into_origin_info(
-                origin_slot.clone().value()
-            ).unwrap();
-            let (symbol, decimals) = decode_slot_into_token_metadata(
-                metadata_slot.clone().value()
-            ).unwrap();
-
-            let receiver_felts = &event.details().inputs().values()[5..8];
-            let receiver_address = felts_to_evm_addresses([
-                receiver_felts[2],
-                receiver_felts[1],
-                receiver_felts[0],
-            ]).unwrap();
-
-            Ok(ExitEvent {
-                note_id: event.id().to_hex(),
-                block_number: block_number.clone().as_u32(),
-                asset: Asset {
-                    origin_address,
-                    origin_network,
-                    decimals,
-                    asset_symbol: symbol.to_string()?
+    let mut tokens: HashMap<AccountId, Account> = HashMap::new();
+    let mut unresolved = Vec::new();
+    let mut resolved = Vec::new();
+    for note in notes {
+        if !tokens.contains_key(&note.sender) {
+            match get_wrapper_account(storage_client, store, note.sender).await? {
+                Some(account) => {
+                    tokens.insert(note.sender, account);
+                },
+                None => {
+                    unresolved.push(note);
+                    continue;
                 },
-                receiver: receiver_address.to_hex_with_prefix(),
-                destination_chain: event.details().inputs().values()[4].as_int(),
-                amount: event.details().inputs().values()[0].as_int(),
-                call_data: None,
-                call_address: None,
-            })
-        }).collect::<Result<Vec<ExitEvent>, OnchainError>>()?
+            }
+        }
+        resolved.push(note);
+    }
+
+    let mut records: HashMap<NoteId, InputNoteRecord> = HashMap::new();
+    if !resolved.is_empty() {
+        let ids = resolved.iter().map(|note| note.note_id).collect();
+        records.extend(
+            storage_client
+                .get_input_notes(NoteFilter::List(ids))
+                .await
+                .map_err(OnchainError::from)?
+                .into_iter()
+                .map(|record| (record.id(), record)),
+        );
+    }
+
+    let mut events = Vec::new();
+    let mut malformed = Vec::new();
+    for note in resolved {
+        let Some(record) = records.get(&note.note_id) else {
+            unresolved.push(note);
+            continue;
+        };
+        match decode_exit_event(record, note.block_number, &tokens[&note.sender]) {
+            Ok(event) => events.push(event),
+            // A malformed note never decodes, skip it instead of stalling the scan
+            Err(e) => {
+                warn!("Skipping malformed bridge note {}: {}", note.note_id, e);
+                malformed.push(note.note_id);
+            },
+        }
+    }
+    events.sort_by_key(|event| event.block_number);
+
+    if !malformed.is_empty() {
+        store.remove_unresolved_exit_notes(malformed).await?;
+    }
+    for event in store.add_exit_events(events, unresolved, scanned_block).await? {
+        // Nobody listening is fine, the events are served from the store on subscription
+        let _ = exit_events.send(event);
+    }
+
+    Ok(scanned_block)
+}
+
+/// Returns the wrapper account of `sender`, imported into the client store when another worker
+/// deployed it.
+///
+/// Only the faucets of the relayer are trusted to emit exits, any other sender is `None`.
+async fn get_wrapper_account(
+    storage_client: &mut Client<FilesystemKeyStore<StdRng>>,
+    store: &Store,
+    sender: AccountId,
+) -> Result<Option<Account>, OnchainError> {
+    if !store.is_relayer_faucet(sender).await? {
+        return Ok(None);
+    }
+    if storage_client.get_account(sender).await?.is_none() {
+        storage_client.import_account_by_id(sender).await?;
+    }
+
+    Ok(storage_client.get_account(sender).await?.map(|record| record.account().clone()))
+}
+
+/// Decodes a bridge note emitted by `token_account` into its exit event.
+fn decode_exit_event(
+    note: &InputNoteRecord,
+    block_number: u32,
+    token_account: &Account,
+) -> Result<ExitEvent, OnchainError> {
+    let origin = TokenWrapperAccount::origin_info(token_account)?;
+    let metadata = TokenWrapperAccount::token_metadata(token_account)?;
+    let origin_address = felts_to_evm_addresses(origin.origin_address())?;
+
+    let inputs = BridgeEventInputs::try_from(note.details().inputs())?;
+    let receiver_address = felts_to_evm_addresses(inputs.dest_address)?;
+    let call_address = inputs.call_address
+        .map(felts_to_evm_addresses)
+        .transpose()?
+        .map(|address| address.to_hex_with_prefix());
+
+    Ok(ExitEvent {
+        note_id: note.id().to_hex(),
+        block_number,
+        asset: Asset {
+            origin_address: origin_address.to_checksum(None),
+            origin_network: u32::try_from(origin.origin_network())?,
+            decimals: metadata.decimals(),
+            asset_symbol: metadata.symbol().to_string()?
+        },
+        receiver: receiver_address.to_hex_with_prefix(),
+        destination_chain: inputs.dest_chain,
+        amount: inputs.asset.amount(),
+        call_data: (!inputs.calldata.is_empty())
+            .then(|| format!("0x{}", hex::encode(&inputs.calldata))),
+        call_address,
     })
-}
\ No newline at end of file
+}
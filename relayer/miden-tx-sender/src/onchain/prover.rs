@@ -0,0 +1,67 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use miden_client::transaction::TransactionProver;
+use miden_remote_prover_client::remote_prover::tx_prover::RemoteTransactionProver;
+use rocket::serde::{Deserialize, Serialize};
+
+/// Prover of a transaction on a remote machine, shared by the mints of the worker.
+pub type RemoteProver = Arc<dyn TransactionProver + Send + Sync>;
+
+/// Where a submitted transaction was proven.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum ProverKind {
+    /// By the relayer itself, either without a remote prover or after the remote one failed.
+    Local,
+    /// By the configured remote prover.
+    Remote,
+}
+
+impl ProverKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProverKind::Local => "local",
+            ProverKind::Remote => "remote",
+        }
+    }
+}
+
+impl fmt::Display for ProverKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ProverKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "local" => Ok(ProverKind::Local),
+            "remote" => Ok(ProverKind::Remote),
+            other => Err(format!("Unknown prover: `{other}`")),
+        }
+    }
+}
+
+/// Connects to the remote prover at `endpoint`. The connection is only opened by the first proof.
+pub fn connect_remote_prover(endpoint: &str) -> RemoteProver {
+    Arc::new(RemoteTransactionProver::new(endpoint))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::ProverKind;
+
+    #[test]
+    fn should_roundtrip_prover_names() {
+        for prover in [ProverKind::Local, ProverKind::Remote] {
+            assert_eq!(ProverKind::from_str(prover.as_str()), Ok(prover));
+        }
+        assert!(ProverKind::from_str("unknown").is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+use tokio::sync::mpsc::{self, Receiver, Sender};
+
+use crate::config::Config;
+use crate::metrics::Metrics;
+use crate::onchain::OnchainClient;
+use crate::onchain::asset::Asset;
+use crate::onchain::client::{client_process_loop, ClientCommand};
+use crate::onchain::poll_events::ExitEventSender;
+use crate::onchain::sync::SyncStatusSender;
+use crate::store::Store;
+
+/// Parameters of the 64-bit FNV-1a hash routing the assets to the workers.
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Delay before the first restart of a failed worker, doubled on every consecutive failure.
+const MIN_RESTART_DELAY: Duration = Duration::from_secs(1);
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(60);
+
+/// Whether the worker is ready to take commands, shared with the routes so they fail fast while
+/// it is (re)starting.
+#[derive(Debug, Clone, Default)]
+pub struct WorkerStatus(Arc<AtomicBool>);
+
+impl WorkerStatus {
+    pub fn is_up(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn set_up(&self, up: bool) {
+        self.0.store(up, Ordering::Release)
+    }
+}
+
+/// Returns the worker of a pool of `pool_size` owning the faucet of the asset at
+/// `origin_address` on `origin_network`.
+///
+/// Every asset is owned by a single worker, so the transactions of a faucet are never executed
+/// concurrently and its nonce increases in order. The faucet lives in the client store of its
+/// worker, so the mapping is a fixed hash that stays the same across builds and restarts.
+pub fn worker_index(origin_network: u32, origin_address: &str, pool_size: usize) -> usize {
+    // Addresses are matched case insensitively, as in the store
+    let bytes = origin_network
+        .to_le_bytes()
+        .into_iter()
+        .chain(origin_address.to_lowercase().into_bytes());
+    let hash =
+        bytes.fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME));
+
+    (hash % pool_size as u64) as usize
+}
+
+/// Everything the worker needs, kept across restarts.
+pub struct Worker {
+    pub(crate) index: usize,
+    pub(crate) pool_size: usize,
+    pub(crate) client: OnchainClient,
+    pub(crate) store: Arc<Store>,
+    pub(crate) exit_events: ExitEventSender,
+    pub(crate) sync_status: Arc<SyncStatusSender>,
+    pub(crate) config: Arc<Config>,
+    pub(crate) metrics: Arc<Metrics>,
+    pub(crate) status: WorkerStatus,
+}
+
+impl Worker {
+    /// Whether the worker scans the exit events and publishes the sync status. Every worker syncs
+    /// its own client store, only the one of the first worker tracks the bridge notes.
+    pub fn scans_exits(&self) -> bool {
+        self.index == 0
+    }
+
+    /// Whether the worker mints `asset`.
+    pub fn owns(&self, asset: &Asset) -> bool {
+        worker_index(asset.origin_network, &asset.origin_address, self.pool_size) == self.index
+    }
+
+    /// Runs the worker on the current thread, restarting it with a growing delay whenever it
+    /// fails or panics. Returns once the command channel is closed.
+    ///
+    /// Interrupted mint jobs are persisted, so the restarted worker picks them up again.
+    pub fn supervise(self, mut receiver: Receiver<ClientCommand>) {
+        let mut restart_delay = MIN_RESTART_DELAY;
+
+        loop {
+            let started_at = Instant::now();
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| {
+                let runtime = tokio::runtime::Builder::new_multi_thread()
+                    .enable_all()
+                    .build()
+                    .expect("Worker runtime to be built");
+                client_process_loop(&self, &mut receiver, runtime)
+            }));
+            self.status.set_up(false);
+
+            match outcome {
+                Ok(Ok(())) => {
+                    info!("Command channel closed, stopping worker {}", self.index);
+                    return;
+                },
+                Ok(Err(e)) => warn!("Worker {} failed: {}", self.index, e),
+                Err(_) => warn!("Worker {} panicked", self.index),
+            }
+
+            // A worker that ran for a while hit a new problem, not the one it restarted for
+            if started_at.elapsed() > MAX_RESTART_DELAY {
+                restart_delay = MIN_RESTART_DELAY;
+            }
+            warn!("Restarting worker {} in {}s", self.index, restart_delay.as_secs());
+            thread::sleep(restart_delay);
+            restart_delay = (restart_delay * 2).min(MAX_RESTART_DELAY);
+        }
+    }
+}
+
+/// Command channel and status of a worker of the pool.
+pub struct WorkerHandle {
+    pub sender: Sender<ClientCommand>,
+    pub status: WorkerStatus,
+}
+
+/// Workers minting in parallel, each owning the faucets of the assets mapped to it by
+/// [`worker_index`].
+///
+/// Every worker runs its own miden client on its own client store, see
+/// [`Config::miden_store_path`]. A single client shared by the pool would only let the proving
+/// run in parallel, while the executions and store writes of every faucet would still wait for
+/// each other. With a store per worker whole mints run concurrently, at the cost of every worker
+/// syncing its store on its own.
+pub struct WorkerPool {
+    workers: Vec<WorkerHandle>,
+}
+
+impl WorkerPool {
+    /// Starts `config.mint_workers()` supervised workers, each on its own thread.
+    pub fn spawn(
+        store: Arc<Store>,
+        exit_events: ExitEventSender,
+        sync_status: SyncStatusSender,
+        config: Arc<Config>,
+        metrics: Arc<Metrics>,
+    ) -> Self {
+        let pool_size = config.mint_workers();
+        let sync_status = Arc::new(sync_status);
+
+        let workers = (0..pool_size)
+            .map(|index| {
+                let (sender, receiver) = mpsc::channel(config.command_channel_size());
+                let worker = Worker {
+                    index,
+                    pool_size,
+                    client: OnchainClient::new(config.rpc_url(), config.rpc_timeout_ms()),
+                    store: store.clone(),
+                    exit_events: exit_events.clone(),
+                    sync_status: sync_status.clone(),
+                    config: config.clone(),
+                    metrics: metrics.clone(),
+                    status: WorkerStatus::default(),
+                };
+                let status = worker.status.clone();
+
+                thread::Builder::new()
+                    .name(format!("worker-{index}"))
+                    .spawn(move || worker.supervise(receiver))
+                    .expect("Worker thread to be spawned");
+
+                WorkerHandle { sender, status }
+            })
+            .collect();
+
+        WorkerPool { workers }
+    }
+
+    /// Returns the worker minting `asset`.
+    pub fn for_asset(&self, asset: &Asset) -> &WorkerHandle {
+        &self.workers[worker_index(asset.origin_network, &asset.origin_address, self.workers.len())]
+    }
+
+    /// Whether every worker is up.
+    pub fn is_up(&self) -> bool {
+        self.workers.iter().all(|worker| worker.status.is_up())
+    }
+
+    /// Commands waiting for the workers.
+    pub fn queue_depth(&self) -> usize {
+        self.workers
+            .iter()
+            .map(|worker| worker.sender.max_capacity() - worker.sender.capacity())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::worker_index;
+
+    const USDC: &str = "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238";
+
+    #[test]
+    fn should_map_an_asset_to_a_single_worker() {
+        let index = worker_index(0, USDC, 4);
+        assert_eq!(worker_index(0, &USDC.to_lowercase(), 4), index);
+        assert_eq!(worker_index(0, USDC, 1), 0);
+    }
+
+    #[test]
+    fn should_map_an_asset_to_the_same_worker_across_builds() {
+        assert_eq!(worker_index(0, USDC, 4), 1);
+        assert_eq!(worker_index(1, USDC, 4), 0);
+        assert_eq!(worker_index(11155111, USDC, 4), 2);
+    }
+}
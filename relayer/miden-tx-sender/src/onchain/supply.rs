@@ -0,0 +1,206 @@
+use miden_bridge::accounts::token_wrapper::TokenWrapperAccount;
+use miden_client::rpc::NodeRpcClient;
+use miden_objects::account::AccountId;
+use rocket::serde::Serialize;
+
+use crate::onchain::asset::Asset;
+use crate::onchain::errors::OnchainError;
+use crate::store::{AssetTotals, Store};
+
+/// Inconsistency between the supply records of a wrapped asset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(
+    crate = "rocket::serde",
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Discrepancy {
+    /// The observed exits burned more than the relayer minted.
+    BurnedExceedsMinted,
+    /// Committed mints are recorded for an asset without a wrapper faucet.
+    FaucetMissing,
+    /// The issuance of the faucet could not be read from the node.
+    IssuanceUnavailable { error: String },
+    /// The issuance of the faucet is outside of the range allowed by the mints and exits.
+    IssuanceMismatch { expected_min: u64, expected_max: u64 },
+}
+
+/// Supply of a registered asset, from the relayer records and from its faucet on chain.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "camelCase")]
+pub struct AssetSupply {
+    #[serde(flatten)]
+    pub asset: Asset,
+    pub faucet_id: Option<String>,
+    /// Sum of the submitted and committed mints.
+    pub minted: u64,
+    /// Part of `minted` whose transactions are not committed yet.
+    pub pending: u64,
+    /// Sum of the observed exits.
+    pub burned: u64,
+    /// Wrapped amount the relayer accounts for, to be backed by the collateral locked on the
+    /// origin network.
+    pub outstanding: u64,
+    /// Total issuance read from the reserved storage slot of the faucet.
+    pub issuance: Option<u64>,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+/// Supply reconciliation of every registered asset.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "camelCase")]
+pub struct SupplyReport {
+    /// Whether no asset has a discrepancy.
+    pub balanced: bool,
+    pub assets: Vec<AssetSupply>,
+}
+
+/// Compares the totals recorded by the relayer with the issuance of the faucet, which is
+/// `Ok(None)` if the asset has no faucet.
+///
+/// Committed mints are on chain while pending ones may not be yet, so the issuance is expected
+/// between the committed and the total minted amounts, net of the burns. Exits are only known up
+/// to the last scanned block, so a burn made since shows as a mismatch until the next scan.
+pub fn reconcile(
+    asset: Asset,
+    faucet_id: Option<AccountId>,
+    totals: Option<&AssetTotals>,
+    issuance: Result<Option<u64>, String>,
+) -> AssetSupply {
+    let (minted, pending, burned) =
+        totals.map_or((0, 0, 0), |totals| (totals.minted, totals.pending, totals.burned));
+    let committed = minted - pending;
+    let mut discrepancies = Vec::new();
+
+    if burned > minted {
+        discrepancies.push(Discrepancy::BurnedExceedsMinted);
+    }
+
+    let issuance = match issuance {
+        Ok(Some(issuance)) => {
+            let expected_min = committed.saturating_sub(burned);
+            let expected_max = minted.saturating_sub(burned);
+            if issuance < expected_min || issuance > expected_max {
+                discrepancies.push(Discrepancy::IssuanceMismatch { expected_min, expected_max });
+            }
+            Some(issuance)
+        },
+        Ok(None) => {
+            if committed > 0 {
+                discrepancies.push(Discrepancy::FaucetMissing);
+            }
+            None
+        },
+        // A faucet deployed ahead of its first mint is not on chain yet
+        Err(_) if minted == 0 => None,
+        Err(error) => {
+            discrepancies.push(Discrepancy::IssuanceUnavailable { error });
+            None
+        },
+    };
+
+    AssetSupply {
+        asset,
+        faucet_id: faucet_id.map(|faucet_id| faucet_id.to_hex()),
+        minted,
+        pending,
+        burned,
+        outstanding: minted.saturating_sub(burned),
+        issuance,
+        discrepancies,
+    }
+}
+
+/// Reads the issuance of a faucet from its public state on the node.
+async fn faucet_issuance(
+    rpc: &(dyn NodeRpcClient + Send + Sync),
+    faucet_id: AccountId,
+) -> Result<u64, OnchainError> {
+    let fetched = rpc.get_account_details(faucet_id).await?;
+    let account = fetched.account().ok_or(OnchainError::AccountNotPublic(faucet_id))?;
+
+    Ok(TokenWrapperAccount::total_issuance(account)?)
+}
+
+/// Reconciles the supply of every registered asset.
+pub async fn supply_report(
+    rpc: &(dyn NodeRpcClient + Send + Sync),
+    store: &Store,
+) -> Result<SupplyReport, OnchainError> {
+    let totals = store.get_asset_totals().await?;
+    let mut assets = Vec::new();
+
+    for registered in store.get_registered_assets().await? {
+        let asset = registered.asset;
+        let asset_totals = totals.iter().find(|totals| {
+            totals.asset.origin_network == asset.origin_network
+                && totals.asset.origin_address.eq_ignore_ascii_case(&asset.origin_address)
+        });
+
+        let faucet_id = store.get_faucet_id(asset.origin_network, &asset.origin_address).await?;
+        let issuance = match faucet_id {
+            Some(faucet_id) => {
+                faucet_issuance(rpc, faucet_id).await.map(Some).map_err(|e| e.to_string())
+            },
+            None => Ok(None),
+        };
+
+        assets.push(reconcile(asset, faucet_id, asset_totals, issuance));
+    }
+
+    Ok(SupplyReport {
+        balanced: assets.iter().all(|asset| asset.discrepancies.is_empty()),
+        assets,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Discrepancy, reconcile};
+    use crate::onchain::asset::Asset;
+    use crate::store::AssetTotals;
+
+    fn usdc() -> Asset {
+        Asset {
+            origin_network: 0,
+            origin_address: "0x1c7d4b196cb0c7b01d743fbc6116a902379c7238".to_string(),
+            asset_symbol: "USDC".to_string(),
+            decimals: 6,
+        }
+    }
+
+    fn totals(minted: u64, pending: u64, burned: u64) -> AssetTotals {
+        AssetTotals { asset: usdc(), minted, pending, burned }
+    }
+
+    #[test]
+    fn should_flag_supply_discrepancies() {
+        // the pending mint may or may not be on chain yet
+        let supply = reconcile(usdc(), None, Some(&totals(1000, 200, 300)), Ok(Some(600)));
+        assert_eq!(supply.outstanding, 700);
+        assert!(supply.discrepancies.is_empty());
+        assert!(reconcile(usdc(), None, Some(&totals(1000, 200, 300)), Ok(Some(700)))
+            .discrepancies
+            .is_empty());
+
+        let supply = reconcile(usdc(), None, Some(&totals(1000, 0, 300)), Ok(Some(900)));
+        assert_eq!(supply.discrepancies, vec![Discrepancy::IssuanceMismatch {
+            expected_min: 700,
+            expected_max: 700
+        }]);
+
+        let supply = reconcile(usdc(), None, Some(&totals(100, 0, 300)), Ok(Some(0)));
+        assert_eq!(supply.discrepancies, vec![Discrepancy::BurnedExceedsMinted]);
+
+        let supply = reconcile(usdc(), None, Some(&totals(100, 0, 0)), Ok(None));
+        assert_eq!(supply.discrepancies, vec![Discrepancy::FaucetMissing]);
+
+        // a faucet without mints is not published yet
+        assert!(reconcile(usdc(), None, None, Err("not found".into())).discrepancies.is_empty());
+        assert_eq!(
+            reconcile(usdc(), None, Some(&totals(100, 0, 0)), Err("not found".into())).discrepancies,
+            vec![Discrepancy::IssuanceUnavailable { error: "not found".into() }]
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use miden_client::Client;
+use miden_client::keystore::FilesystemKeyStore;
+use log::warn;
+use rand::rngs::StdRng;
+use tokio::sync::watch;
+
+use crate::onchain::client::OnchainClient;
+use crate::onchain::errors::OnchainError;
+use crate::onchain::poll_events::{poll_events, ExitEventSender};
+use crate::onchain::tracker::track_transactions;
+use crate::store::Store;
+
+/// Outcome of the last sync of the client, read by the request handlers instead of syncing or
+/// calling the node on their own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncStatus {
+    /// Block the client store is synced to, `None` until the first sync succeeds.
+    pub sync_height: Option<u32>,
+    /// Unix time of the last successful sync, in seconds.
+    pub synced_at: Option<u64>,
+    /// Chain tip the node answered at the start of the last sync, `None` if it did not answer.
+    pub chain_tip: Option<u32>,
+}
+
+pub type SyncStatusSender = watch::Sender<SyncStatus>;
+pub type SyncStatusReceiver = watch::Receiver<SyncStatus>;
+
+/// Syncs the client store of a worker, then resolves the tracked transactions from the synced
+/// state.
+///
+/// Failing to track the transactions is only logged, the next sync resolves them.
+pub async fn sync_worker_client(
+    execution_client: &mut Client<FilesystemKeyStore<StdRng>>,
+    store: &Store,
+) -> Result<(), OnchainError> {
+    execution_client.sync_state().await?;
+
+    if let Err(e) = track_transactions(execution_client, store).await {
+        warn!("Transaction tracking failed: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Reads the chain tip, syncs the client, then resolves the tracked transactions and scans the
+/// exit events from the synced state. Both steps are published to `sync_status`.
+///
+/// The chain tip is published even when the sync fails, so the sync lag grows while the client
+/// store falls behind. Failing to track the transactions or to scan the exits is only logged,
+/// so it does not hold back the sync height.
+pub async fn sync_client(
+    execution_client: &mut Client<FilesystemKeyStore<StdRng>>,
+    client: &OnchainClient,
+    store: &Store,
+    exit_events: &ExitEventSender,
+    sync_status: &SyncStatusSender,
+) -> Result<(), OnchainError> {
+    let chain_tip = match client.get_chain_tip().await {
+        Ok(chain_tip) => Some(chain_tip.as_u32()),
+        Err(e) => {
+            warn!("Node is unreachable: {}", e);
+            None
+        },
+    };
+    sync_status.send_modify(|status| status.chain_tip = chain_tip);
+
+    sync_worker_client(execution_client, store).await?;
+
+    let sync_height = match poll_events(execution_client, &*client.rpc, store, exit_events).await {
+        Ok(scanned_block) => scanned_block,
+        Err(e) => {
+            warn!("Exit scanning failed: {}", e);
+            execution_client.get_sync_height().await?.as_u32()
+        },
+    };
+
+    let synced_at =
+        SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards").as_secs();
+
+    sync_status.send_modify(|status| {
+        status.sync_height = Some(sync_height);
+        status.synced_at = Some(synced_at);
+    });
+
+    Ok(())
+}
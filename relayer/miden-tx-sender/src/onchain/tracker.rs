@@ -0,0 +1,237 @@
+use std::fmt;
+use std::str::FromStr;
+
+use miden_client::Client;
+use miden_client::keystore::FilesystemKeyStore;
+use miden_client::rpc::NodeRpcClient;
+use miden_client::store::TransactionFilter;
+use miden_client::transaction::{TransactionId, TransactionStatus};
+use miden_objects::Word;
+use miden_objects::note::NoteId;
+use miden_objects::utils::parse_hex_string_as_word;
+use rand::rngs::StdRng;
+use rocket::serde::{Deserialize, Serialize};
+
+use crate::onchain::errors::OnchainError;
+use crate::onchain::mint_job::MintJob;
+use crate::onchain::prover::ProverKind;
+use crate::store::Store;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum TrackedTransactionStatus {
+    /// Submitted to the node, not yet seen in a block.
+    Pending,
+    /// Included in a block.
+    Committed,
+    /// Dropped by the client, the reason is stored alongside.
+    Discarded,
+}
+
+impl TrackedTransactionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrackedTransactionStatus::Pending => "pending",
+            TrackedTransactionStatus::Committed => "committed",
+            TrackedTransactionStatus::Discarded => "discarded",
+        }
+    }
+}
+
+impl fmt::Display for TrackedTransactionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TrackedTransactionStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(TrackedTransactionStatus::Pending),
+            "committed" => Ok(TrackedTransactionStatus::Committed),
+            "discarded" => Ok(TrackedTransactionStatus::Discarded),
+            other => Err(format!("Unknown transaction status: `{other}`")),
+        }
+    }
+}
+
+/// A transaction submitted by the relayer, watched until it is committed or discarded.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "camelCase")]
+pub struct TrackedTransaction {
+    pub transaction_id: String,
+    pub status: TrackedTransactionStatus,
+    pub committed_block: Option<u32>,
+    pub discard_reason: Option<String>,
+    pub submitted_at: u64,
+    pub updated_at: u64,
+    /// Where the transaction was proven, unknown for a transaction recovered after a restart.
+    pub prover: Option<ProverKind>,
+}
+
+pub fn parse_transaction_id(hex: &str) -> Result<TransactionId, OnchainError> {
+    Ok(TransactionId::from(Word::from(parse_hex_string_as_word(hex)?)))
+}
+
+/// What the node tells about the transaction of a mint job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeMintOutcome {
+    /// The note of the job is in the block.
+    Committed(u32),
+    /// The note is not on chain and the transaction expired, so it never will be.
+    Absent,
+    /// The note is not on chain yet, but the transaction may still be included.
+    Unknown,
+}
+
+impl NodeMintOutcome {
+    /// Settles the outcome of a job transaction expiring after `expiration_block`, from the block
+    /// its note was found in and the block the node state is known up to.
+    pub fn new(
+        committed_block: Option<u32>,
+        expiration_block: Option<u32>,
+        known_up_to: u32,
+    ) -> Self {
+        match (committed_block, expiration_block) {
+            (Some(block), _) => NodeMintOutcome::Committed(block),
+            (None, Some(expiration)) if known_up_to > expiration => NodeMintOutcome::Absent,
+            // A transaction without a known expiration may be included at any time
+            (None, _) => NodeMintOutcome::Unknown,
+        }
+    }
+}
+
+/// Looks the note of `job` up on the node, for a transaction the client store does not know,
+/// e.g. one the node accepted right before the relayer stopped.
+///
+/// The sync height bounds the expiration check, so the note of an expired transaction had the
+/// time to be fetched by the sync before it is reported absent.
+pub async fn check_mint_on_node(
+    rpc: &(dyn NodeRpcClient + Send + Sync),
+    job: &MintJob,
+    sync_height: u32,
+) -> Result<NodeMintOutcome, OnchainError> {
+    let Some(note_id) = job.note_id.as_deref() else {
+        return Ok(NodeMintOutcome::new(None, job.expiration_block, sync_height));
+    };
+
+    let notes = rpc.get_notes_by_id(&[NoteId::try_from_hex(note_id)?]).await?;
+    let committed_block =
+        notes.first().map(|note| note.inclusion_proof().location().block_num().as_u32());
+
+    Ok(NodeMintOutcome::new(committed_block, job.expiration_block, sync_height))
+}
+
+/// Resolves the pending tracked transactions against the client store. Every worker submits to
+/// its own store, the transactions it does not know are left to the worker that submitted them.
+///
+/// Only reads the state of the last sync, the caller is responsible for syncing the client.
+pub async fn track_transactions(
+    execution_client: &mut Client<FilesystemKeyStore<StdRng>>,
+    store: &Store,
+) -> Result<(), OnchainError> {
+    let pending = store.get_pending_transactions().await?;
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let ids = pending
+        .iter()
+        .map(|tx| parse_transaction_id(&tx.transaction_id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let records = execution_client.get_transactions(TransactionFilter::Ids(ids)).await?;
+
+    for record in records {
+        match record.status {
+            TransactionStatus::Pending => {},
+            TransactionStatus::Committed { block_number, .. } => {
+                store.set_transaction_committed(record.id.to_hex(), block_number.as_u32()).await?;
+            },
+            TransactionStatus::Discarded(cause) => {
+                store.set_transaction_discarded(record.id.to_hex(), cause.to_string()).await?;
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Settles from the node the pending transactions of the jobs `owns` selects that the client
+/// store does not know, e.g. ones whose submission failed without telling whether the node
+/// accepted them. Their jobs are committed once the node has a note of theirs, and failed once
+/// the transaction expired without it, so a replayed request mints them again.
+pub async fn settle_unknown_mint_transactions(
+    execution_client: &mut Client<FilesystemKeyStore<StdRng>>,
+    rpc: &(dyn NodeRpcClient + Send + Sync),
+    store: &Store,
+    owns: impl Fn(&MintJob) -> bool,
+) -> Result<(), OnchainError> {
+    // The notes of a transaction are created together, one job per transaction is enough
+    let mut jobs: Vec<MintJob> = Vec::new();
+    for job in store.get_pending_mint_jobs().await? {
+        if owns(&job) && jobs.iter().all(|known| known.transaction_id != job.transaction_id) {
+            jobs.push(job);
+        }
+    }
+
+    if jobs.is_empty() {
+        return Ok(());
+    }
+
+    let ids = jobs
+        .iter()
+        .filter_map(|job| job.parsed_transaction_id().transpose())
+        .collect::<Result<Vec<_>, _>>()?;
+    let known: Vec<String> = execution_client
+        .get_transactions(TransactionFilter::Ids(ids))
+        .await?
+        .iter()
+        .map(|record| record.id.to_hex())
+        .collect();
+    let sync_height = execution_client.get_sync_height().await?.as_u32();
+
+    for job in jobs {
+        let Some(transaction_id) = job.transaction_id.clone() else {
+            continue;
+        };
+        // The client store settles the transactions it knows, see `track_transactions`
+        if known.contains(&transaction_id) {
+            continue;
+        }
+
+        match check_mint_on_node(rpc, &job, sync_height).await? {
+            NodeMintOutcome::Committed(block) => {
+                store.set_transaction_committed(transaction_id, block).await?;
+            },
+            NodeMintOutcome::Absent => {
+                store
+                    .set_transaction_discarded(
+                        transaction_id,
+                        "expired without reaching the chain".to_string(),
+                    )
+                    .await?;
+            },
+            NodeMintOutcome::Unknown => {},
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NodeMintOutcome;
+
+    #[test]
+    fn should_only_report_an_expired_transaction_absent() {
+        assert_eq!(NodeMintOutcome::new(Some(90), Some(120), 100), NodeMintOutcome::Committed(90));
+        assert_eq!(NodeMintOutcome::new(Some(90), Some(120), 130), NodeMintOutcome::Committed(90));
+        assert_eq!(NodeMintOutcome::new(None, Some(120), 120), NodeMintOutcome::Unknown);
+        assert_eq!(NodeMintOutcome::new(None, Some(120), 121), NodeMintOutcome::Absent);
+        assert_eq!(NodeMintOutcome::new(None, None, 1000), NodeMintOutcome::Unknown);
+    }
+}
@@ -0,0 +1,534 @@
+use miden_client::store::StoreError;
+use miden_client::utils::{Deserializable, Serializable};
+use miden_objects::account::AccountId;
+use miden_objects::note::NoteId;
+use rusqlite::types::{Type, Value};
+use rusqlite::{OptionalExtension, Row, Transaction, params, params_from_iter};
+
+use super::{Store, now_seconds};
+use crate::onchain::asset::Asset;
+use crate::onchain::poll_events::{
+    ExitCursor, ExitDeliveryStatus, ExitEvent, ExitQuery, UnresolvedExitNote,
+};
+
+const EXIT_EVENT_COLUMNS: &str = "exit_events.note_id, exit_events.block_number,
+    exit_events.origin_network, exit_events.origin_address, exit_events.asset_symbol,
+    exit_events.decimals, exit_events.receiver, exit_events.destination_chain,
+    exit_events.amount, exit_events.call_address, exit_events.call_data";
+
+fn exit_event_from_row(row: &Row<'_>) -> rusqlite::Result<ExitEvent> {
+    Ok(ExitEvent {
+        note_id: row.get(0)?,
+        block_number: row.get(1)?,
+        asset: Asset {
+            origin_network: row.get(2)?,
+            origin_address: row.get(3)?,
+            asset_symbol: row.get(4)?,
+            decimals: row.get(5)?,
+        },
+        receiver: row.get(6)?,
+        destination_chain: row.get(7)?,
+        amount: row.get(8)?,
+        call_address: row.get(9)?,
+        call_data: row.get(10)?,
+    })
+}
+
+fn unresolved_exit_note_from_row(row: &Row<'_>) -> rusqlite::Result<UnresolvedExitNote> {
+    let note_id: String = row.get(0)?;
+    let sender: Vec<u8> = row.get(2)?;
+    Ok(UnresolvedExitNote {
+        note_id: NoteId::try_from_hex(&note_id).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(note_id.len(), Type::Text, Box::new(e))
+        })?,
+        block_number: row.get(1)?,
+        sender: AccountId::read_from_bytes(&sender).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(sender.len(), Type::Blob, Box::new(e))
+        })?,
+    })
+}
+
+/// Returns the consumer position, 0 if it never acknowledged an event.
+fn exit_cursor_position(tx: &Transaction<'_>, consumer: &str) -> rusqlite::Result<u64> {
+    Ok(tx
+        .query_row(
+            "SELECT exit_id FROM exit_cursors WHERE consumer = ?1",
+            params![consumer],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(0))
+}
+
+impl Store {
+    /// Returns the last block whose bridge notes were scanned into the exit events.
+    pub async fn get_exit_scan_block(&self) -> Result<Option<u32>, StoreError> {
+        self.pool
+            .conn(move |conn| {
+                conn.query_row("SELECT block_number FROM exit_scan_cursor", [], |row| row.get(0))
+                    .optional()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Persists newly observed exit events, records the `unresolved` bridge notes for a retry and
+    /// moves the scan cursor to `scanned_block`.
+    ///
+    /// Already known notes are ignored, so a block can be scanned again safely, and a retried note
+    /// is no longer unresolved once its event is persisted. Returns the inserted events with
+    /// their position.
+    pub async fn add_exit_events(
+        &self,
+        events: Vec<ExitEvent>,
+        unresolved: Vec<UnresolvedExitNote>,
+        scanned_block: u32,
+    ) -> Result<Vec<(u64, ExitEvent)>, StoreError> {
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                let tx = conn.transaction()?;
+                let mut inserted = Vec::new();
+                for event in events {
+                    let changes = tx.execute(
+                        "INSERT INTO exit_events (note_id, block_number, origin_network,
+                            origin_address, asset_symbol, decimals, receiver, destination_chain,
+                            amount, call_address, call_data, created_at)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+                         ON CONFLICT (note_id) DO NOTHING",
+                        params![
+                            &event.note_id,
+                            event.block_number,
+                            event.asset.origin_network,
+                            &event.asset.origin_address,
+                            &event.asset.asset_symbol,
+                            event.asset.decimals,
+                            &event.receiver,
+                            event.destination_chain,
+                            event.amount,
+                            &event.call_address,
+                            &event.call_data,
+                            now,
+                        ],
+                    )?;
+                    let id = tx.last_insert_rowid() as u64;
+                    tx.execute(
+                        "DELETE FROM unresolved_exit_notes WHERE note_id = ?1",
+                        params![&event.note_id],
+                    )?;
+                    if changes > 0 {
+                        inserted.push((id, event));
+                    }
+                }
+                for note in unresolved {
+                    tx.execute(
+                        "INSERT INTO unresolved_exit_notes (note_id, block_number, sender,
+                            created_at)
+                         VALUES (?1, ?2, ?3, ?4)
+                         ON CONFLICT (note_id) DO NOTHING",
+                        params![
+                            note.note_id.to_hex(),
+                            note.block_number,
+                            note.sender.to_bytes(),
+                            now,
+                        ],
+                    )?;
+                }
+                tx.execute(
+                    "INSERT INTO exit_scan_cursor (id, block_number, updated_at) VALUES (0, ?1, ?2)
+                     ON CONFLICT (id) DO UPDATE SET block_number = ?1, updated_at = ?2",
+                    params![scanned_block, now],
+                )?;
+                tx.commit()?;
+                Ok(inserted)
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Returns the unresolved bridge notes whose sender became a faucet of the relayer, the
+    /// notes of any other sender are never trusted to emit an exit.
+    pub async fn get_resolvable_exit_notes(&self) -> Result<Vec<UnresolvedExitNote>, StoreError> {
+        self.pool
+            .conn(move |conn| {
+                let mut stmt = conn.prepare(
+                    "SELECT note_id, block_number, sender FROM unresolved_exit_notes
+                     WHERE sender IN (SELECT miden_faucet_id FROM assets_info)
+                     ORDER BY block_number",
+                )?;
+                stmt.query_map([], unresolved_exit_note_from_row)?.collect()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Stops retrying the unresolved bridge notes recorded at least `retention_secs` ago,
+    /// returning them.
+    pub async fn prune_unresolved_exit_notes(
+        &self,
+        retention_secs: u64,
+    ) -> Result<Vec<UnresolvedExitNote>, StoreError> {
+        let created_before = now_seconds().saturating_sub(retention_secs);
+
+        self.pool
+            .conn_mut(move |conn| {
+                let tx = conn.transaction()?;
+                let pruned = tx
+                    .prepare(
+                        "SELECT note_id, block_number, sender FROM unresolved_exit_notes
+                         WHERE created_at <= ?1 ORDER BY block_number",
+                    )?
+                    .query_map(params![created_before], unresolved_exit_note_from_row)?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                tx.execute(
+                    "DELETE FROM unresolved_exit_notes WHERE created_at <= ?1",
+                    params![created_before],
+                )?;
+                tx.commit()?;
+                Ok(pruned)
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Stops retrying the unresolved bridge notes that can never become an exit event.
+    pub async fn remove_unresolved_exit_notes(
+        &self,
+        note_ids: Vec<NoteId>,
+    ) -> Result<(), StoreError> {
+        self.pool
+            .conn_mut(move |conn| {
+                let tx = conn.transaction()?;
+                for note_id in note_ids {
+                    tx.execute(
+                        "DELETE FROM unresolved_exit_notes WHERE note_id = ?1",
+                        params![note_id.to_hex()],
+                    )?;
+                }
+                tx.commit()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Returns a page of the exit events matching `query` with their position, regardless of
+    /// their delivery.
+    ///
+    /// Only the requested filters end up in the statement, so each of them can use its index.
+    pub async fn query_exit_events(
+        &self,
+        query: ExitQuery,
+    ) -> Result<Vec<(u64, ExitEvent)>, StoreError> {
+        let mut conditions = vec!["exit_events.id > ?".to_string()];
+        let mut values = vec![Value::Integer(query.after.unwrap_or(0) as i64)];
+
+        if let Some(from_block) = query.from_block {
+            conditions.push("exit_events.block_number >= ?".into());
+            values.push(Value::Integer(from_block.into()));
+        }
+        if let Some(destination_chain) = query.filter.destination_chain {
+            conditions.push("exit_events.destination_chain = ?".into());
+            values.push(Value::Integer(destination_chain as i64));
+        }
+        if let Some(origin_network) = query.filter.origin_network {
+            conditions.push("exit_events.origin_network = ?".into());
+            values.push(Value::Integer(origin_network.into()));
+        }
+        if let Some(origin_address) = query.filter.origin_address {
+            conditions.push("exit_events.origin_address = ? COLLATE NOCASE".into());
+            values.push(Value::Text(origin_address));
+        }
+        if let Some(receiver) = query.filter.receiver {
+            conditions.push("exit_events.receiver = ? COLLATE NOCASE".into());
+            values.push(Value::Text(receiver));
+        }
+        if let Some(note_id) = query.filter.note_id {
+            conditions.push("exit_events.note_id = ?".into());
+            values.push(Value::Text(note_id.to_lowercase()));
+        }
+        // A negative limit is no limit in SQLite
+        values.push(Value::Integer(query.limit.map_or(-1, i64::from)));
+
+        self.pool
+            .conn(move |conn| {
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT {EXIT_EVENT_COLUMNS}, exit_events.id FROM exit_events
+                     WHERE {} ORDER BY exit_events.id LIMIT ?",
+                    conditions.join(" AND ")
+                ))?;
+                stmt.query_map(params_from_iter(values), |row| {
+                    Ok((row.get(11)?, exit_event_from_row(row)?))
+                })?
+                .collect()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Returns the exit events `consumer` did not acknowledge yet and records their delivery.
+    ///
+    /// Delivered events are returned again until they are acknowledged, at most `limit` at once
+    /// if set.
+    pub async fn deliver_exit_events(
+        &self,
+        consumer: String,
+        limit: Option<u32>,
+    ) -> Result<Vec<ExitEvent>, StoreError> {
+        let now = now_seconds();
+        let limit = limit.map_or(-1, i64::from);
+
+        self.pool
+            .conn_mut(move |conn| {
+                let tx = conn.transaction()?;
+                let position = exit_cursor_position(&tx, &consumer)?;
+
+                let pending = {
+                    let mut stmt = tx.prepare(&format!(
+                        "SELECT {EXIT_EVENT_COLUMNS}, exit_events.id FROM exit_events
+                         LEFT JOIN exit_deliveries ON exit_deliveries.exit_id = exit_events.id
+                            AND exit_deliveries.consumer = ?1
+                         WHERE exit_events.id > ?2
+                            AND (exit_deliveries.status IS NULL OR exit_deliveries.status != ?3)
+                         ORDER BY exit_events.id LIMIT ?4"
+                    ))?;
+                    stmt.query_map(
+                        params![consumer, position, ExitDeliveryStatus::Acknowledged.as_str(), limit],
+                        |row| Ok((row.get::<_, u64>(11)?, exit_event_from_row(row)?)),
+                    )?
+                    .collect::<rusqlite::Result<Vec<_>>>()?
+                };
+
+                for (id, _) in &pending {
+                    tx.execute(
+                        "INSERT INTO exit_deliveries
+                            (consumer, exit_id, status, attempts, delivered_at)
+                         VALUES (?1, ?2, ?3, 1, ?4)
+                         ON CONFLICT (consumer, exit_id) DO UPDATE
+                            SET attempts = attempts + 1, delivered_at = ?4",
+                        params![consumer, id, ExitDeliveryStatus::Delivered.as_str(), now],
+                    )?;
+                }
+                tx.commit()?;
+
+                Ok(pending.into_iter().map(|(_, event)| event).collect())
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Acknowledges exit events for `consumer` and moves its cursor past the events acknowledged
+    /// without gaps. Unknown note ids are ignored.
+    pub async fn acknowledge_exit_events(
+        &self,
+        consumer: String,
+        note_ids: Vec<String>,
+    ) -> Result<ExitCursor, StoreError> {
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                let tx = conn.transaction()?;
+
+                let mut acknowledged = 0;
+                for note_id in note_ids {
+                    acknowledged += tx.execute(
+                        "INSERT INTO exit_deliveries
+                            (consumer, exit_id, status, attempts, delivered_at, acknowledged_at)
+                         SELECT ?1, id, ?3, 0, ?4, ?4 FROM exit_events WHERE note_id = ?2
+                         ON CONFLICT (consumer, exit_id) DO UPDATE
+                            SET status = ?3, acknowledged_at = ?4
+                            WHERE status != ?3",
+                        params![
+                            consumer,
+                            note_id.to_lowercase(),
+                            ExitDeliveryStatus::Acknowledged.as_str(),
+                            now
+                        ],
+                    )?;
+                }
+
+                // The cursor stops right before the first event not acknowledged yet
+                let position = exit_cursor_position(&tx, &consumer)?;
+                let first_pending: Option<u64> = tx.query_row(
+                    "SELECT MIN(exit_events.id) FROM exit_events
+                     LEFT JOIN exit_deliveries ON exit_deliveries.exit_id = exit_events.id
+                        AND exit_deliveries.consumer = ?1 AND exit_deliveries.status = ?3
+                     WHERE exit_events.id > ?2 AND exit_deliveries.exit_id IS NULL",
+                    params![consumer, position, ExitDeliveryStatus::Acknowledged.as_str()],
+                    |row| row.get(0),
+                )?;
+                let position = match first_pending {
+                    Some(id) => id - 1,
+                    None => tx.query_row(
+                        "SELECT COALESCE(MAX(id), ?1) FROM exit_events",
+                        params![position],
+                        |row| row.get(0),
+                    )?,
+                };
+
+                tx.execute(
+                    "INSERT INTO exit_cursors (consumer, exit_id, updated_at) VALUES (?1, ?2, ?3)
+                     ON CONFLICT (consumer) DO UPDATE SET exit_id = ?2, updated_at = ?3",
+                    params![consumer, position, now],
+                )?;
+                let block_number = tx
+                    .query_row(
+                        "SELECT block_number FROM exit_events WHERE id = ?1",
+                        params![position],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                tx.commit()?;
+
+                Ok(ExitCursor { consumer, position, block_number, acknowledged })
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountId;
+    use miden_objects::note::NoteId;
+
+    use super::Store;
+    use crate::onchain::asset::Asset;
+    use crate::onchain::poll_events::{ExitEvent, ExitFilter, ExitQuery, UnresolvedExitNote};
+    use crate::store::test_store_path;
+
+    fn exit_event(note_id: &str, block_number: u32) -> ExitEvent {
+        ExitEvent {
+            note_id: note_id.to_string(),
+            block_number,
+            asset: Asset {
+                origin_network: 0,
+                origin_address: "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238".to_string(),
+                asset_symbol: "USDC".to_string(),
+                decimals: 6,
+            },
+            receiver: "0xab348cb6a2bf1ae152c793e091ff0545cf0ad7b7".to_string(),
+            destination_chain: 11155111,
+            amount: 1000,
+            call_address: None,
+            call_data: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_deliver_exit_events_until_acknowledged() {
+        let path = test_store_path("exits");
+        let store = Store::new(path.clone()).await.unwrap();
+        let events = vec![exit_event("0x01", 10), exit_event("0x02", 11), exit_event("0x03", 12)];
+        let inserted = store.add_exit_events(events.clone(), vec![], 12).await.unwrap();
+        assert_eq!(inserted.iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        // scanning the same block again does not duplicate the events
+        assert!(store.add_exit_events(events[2..].to_vec(), vec![], 12).await.unwrap().is_empty());
+
+        assert_eq!(store.deliver_exit_events("evm".into(), None).await.unwrap(), events);
+        assert_eq!(store.deliver_exit_events("other".into(), Some(1)).await.unwrap(), events[..1]);
+
+        let cursor = store
+            .acknowledge_exit_events("evm".into(), vec!["0x01".into(), "0x03".into()])
+            .await
+            .unwrap();
+        assert_eq!((cursor.position, cursor.block_number, cursor.acknowledged), (1, Some(10), 2));
+
+        // the state survives a restart
+        drop(store);
+        let store = Store::new(path).await.unwrap();
+        assert_eq!(store.get_exit_scan_block().await.unwrap(), Some(12));
+        assert_eq!(store.deliver_exit_events("evm".into(), None).await.unwrap(), events[1..2]);
+
+        let cursor =
+            store.acknowledge_exit_events("evm".into(), vec!["0x02".into()]).await.unwrap();
+        assert_eq!((cursor.position, cursor.block_number), (3, Some(12)));
+        assert!(store.deliver_exit_events("evm".into(), Some(100)).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_page_filtered_exit_events() {
+        let store = Store::new(test_store_path("exit-query")).await.unwrap();
+        let mut events: Vec<ExitEvent> =
+            (1..=5).map(|i| exit_event(&format!("0x0{i}"), 10 + i)).collect();
+        events[1].destination_chain = 1;
+        events[3].receiver = "0x20b0bad0c3c9c3f40a88801a5e8e24043b9c6c10".to_string();
+        store.add_exit_events(events.clone(), vec![], 15).await.unwrap();
+
+        let query = ExitQuery { limit: Some(2), ..Default::default() };
+        let page = store.query_exit_events(query.clone()).await.unwrap();
+        assert_eq!(page, vec![(1, events[0].clone()), (2, events[1].clone())]);
+
+        let page =
+            store.query_exit_events(ExitQuery { after: Some(4), ..query.clone() }).await.unwrap();
+        assert_eq!(page, vec![(5, events[4].clone())]);
+
+        let filter = ExitFilter { destination_chain: Some(11155111), ..Default::default() };
+        let unlimited = ExitQuery { from_block: Some(13), limit: None, filter, ..query.clone() };
+        let page = store.query_exit_events(unlimited).await.unwrap();
+        assert_eq!(page, vec![(3, events[2].clone()), (4, events[3].clone()), (5, events[4].clone())]);
+
+        let filter = ExitFilter {
+            receiver: Some("0x20B0bad0c3C9C3f40A88801A5E8e24043B9c6C10".to_string()),
+            origin_address: Some("0x1c7d4b196cb0c7b01d743fbc6116a902379c7238".to_string()),
+            ..Default::default()
+        };
+        let page = store.query_exit_events(ExitQuery { filter, ..query }).await.unwrap();
+        assert_eq!(page, vec![(4, events[3].clone())]);
+    }
+
+    #[tokio::test]
+    async fn should_retry_the_unresolved_notes_of_relayer_faucets() {
+        let store = Store::new(test_store_path("unresolved-exits")).await.unwrap();
+        let sender = AccountId::from_hex("0x8a65fc5a39e4cd106d648e3eb4ab5f").unwrap();
+        let note = |first_byte: &str, block_number| UnresolvedExitNote {
+            note_id: NoteId::try_from_hex(&format!("0x{first_byte}{}", "0".repeat(62))).unwrap(),
+            block_number,
+            sender,
+        };
+        let notes = vec![note("01", 10), note("02", 11)];
+        store.add_exit_events(vec![], notes.clone(), 11).await.unwrap();
+        assert_eq!(store.get_exit_scan_block().await.unwrap(), Some(11));
+        // the notes of a sender that is not a faucet of the relayer are never retried
+        assert!(!store.is_relayer_faucet(sender).await.unwrap());
+        assert!(store.get_resolvable_exit_notes().await.unwrap().is_empty());
+
+        store
+            .add_faucet_id(0, "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238", &sender)
+            .await
+            .unwrap();
+        assert!(store.is_relayer_faucet(sender).await.unwrap());
+        assert_eq!(store.get_resolvable_exit_notes().await.unwrap(), notes);
+
+        // persisting the event of a retried note resolves it
+        let event = exit_event(&notes[0].note_id.to_hex(), 10);
+        store.add_exit_events(vec![event], vec![], 12).await.unwrap();
+        assert_eq!(store.get_resolvable_exit_notes().await.unwrap(), notes[1..]);
+
+        store.remove_unresolved_exit_notes(vec![notes[1].note_id]).await.unwrap();
+        assert!(store.get_resolvable_exit_notes().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_prune_the_unresolved_notes_past_their_retention() {
+        let store = Store::new(test_store_path("pruned-exits")).await.unwrap();
+        let sender = AccountId::from_hex("0x8a65fc5a39e4cd106d648e3eb4ab5f").unwrap();
+        let note = UnresolvedExitNote {
+            note_id: NoteId::try_from_hex(&format!("0x01{}", "0".repeat(62))).unwrap(),
+            block_number: 10,
+            sender,
+        };
+        store.add_exit_events(vec![], vec![note.clone()], 10).await.unwrap();
+        store
+            .add_faucet_id(0, "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238", &sender)
+            .await
+            .unwrap();
+
+        assert!(store.prune_unresolved_exit_notes(60).await.unwrap().is_empty());
+        assert_eq!(store.get_resolvable_exit_notes().await.unwrap(), vec![note.clone()]);
+
+        assert_eq!(store.prune_unresolved_exit_notes(0).await.unwrap(), vec![note]);
+        assert!(store.get_resolvable_exit_notes().await.unwrap().is_empty());
+    }
+}
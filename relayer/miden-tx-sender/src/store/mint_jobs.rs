@@ -0,0 +1,734 @@
+use std::str::FromStr;
+
+use miden_client::store::StoreError;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+
+use super::registry::query_mint_limits;
+use super::{Store, now_seconds};
+use crate::onchain::asset::Asset;
+use crate::onchain::deposit::DepositId;
+use crate::onchain::limits::MINT_LIMIT_WINDOW_SECS;
+use crate::onchain::mint_job::{MintJob, MintJobState};
+use crate::onchain::mint_note::MintArgs;
+use crate::onchain::prover::ProverKind;
+use crate::onchain::tracker::TrackedTransactionStatus;
+
+const MINT_JOB_COLUMNS: &str = "mint_jobs.id, mint_jobs.origin_network, mint_jobs.tx_hash,
+    mint_jobs.deposit_count, mint_jobs.asset_origin_network, mint_jobs.asset_origin_address,
+    mint_jobs.asset_symbol, mint_jobs.asset_decimals, mint_jobs.amount, mint_jobs.recipient,
+    mint_jobs.state, mint_jobs.faucet_id, mint_jobs.note_id, mint_jobs.transaction_id,
+    mint_jobs.error, mint_jobs.created_at, mint_jobs.updated_at,
+    tracked_transactions.committed_block, mint_jobs.note_type, mint_jobs.recipient_kind,
+    tracked_transactions.prover, mint_jobs.expiration_block";
+
+const MINT_JOB_FROM: &str = "mint_jobs LEFT JOIN tracked_transactions
+    ON tracked_transactions.transaction_id = mint_jobs.transaction_id";
+
+/// Reads a column holding the name of an enum variant.
+fn parse_column<T: FromStr<Err = String>>(row: &Row<'_>, index: usize) -> rusqlite::Result<T> {
+    let value: String = row.get(index)?;
+    T::from_str(&value).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, e.into())
+    })
+}
+
+fn mint_job_from_row(row: &Row<'_>) -> rusqlite::Result<MintJob> {
+    let prover: Option<String> = row.get(20)?;
+    let prover = prover.map(|prover| ProverKind::from_str(&prover)).transpose().map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(20, rusqlite::types::Type::Text, e.into())
+    })?;
+
+    Ok(MintJob {
+        id: row.get(0)?,
+        deposit: DepositId {
+            origin_network: row.get(1)?,
+            tx_hash: row.get(2)?,
+            deposit_count: row.get(3)?,
+        },
+        asset: Asset {
+            origin_network: row.get(4)?,
+            origin_address: row.get(5)?,
+            asset_symbol: row.get(6)?,
+            decimals: row.get(7)?,
+        },
+        amount: row.get(8)?,
+        recipient: row.get(9)?,
+        recipient_kind: parse_column(row, 19)?,
+        note_type: parse_column(row, 18)?,
+        state: parse_column(row, 10)?,
+        faucet_id: row.get(11)?,
+        note_id: row.get(12)?,
+        transaction_id: row.get(13)?,
+        expiration_block: row.get(21)?,
+        error: row.get(14)?,
+        committed_block: row.get(17)?,
+        prover,
+        created_at: row.get(15)?,
+        updated_at: row.get(16)?,
+    })
+}
+
+/// Job of a deposit found by a new request for the same deposit.
+struct KnownJob {
+    state: String,
+    amount: u64,
+    recipient: String,
+    /// Status of the last transaction of the job, if it was tracked.
+    transaction_status: Option<String>,
+}
+
+/// Sums the amounts of an asset minted, or about to be, since `since`, only to `recipient` if set.
+///
+/// Jobs are dated by their request, so later state changes do not move them into a new window.
+fn minted_since(
+    conn: &Connection,
+    origin_network: u32,
+    origin_address: &str,
+    recipient: Option<&str>,
+    since: u64,
+) -> rusqlite::Result<u64> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(amount), 0) FROM mint_jobs
+         WHERE asset_origin_network = ?1 AND asset_origin_address = ?2 COLLATE NOCASE
+            AND state IN (?5, ?6, ?7, ?8)
+            AND created_at > ?3 AND (?4 IS NULL OR recipient = ?4)",
+        params![
+            origin_network,
+            origin_address,
+            since,
+            recipient,
+            MintJobState::Queued.as_str(),
+            MintJobState::Executing.as_str(),
+            MintJobState::Submitted.as_str(),
+            MintJobState::Committed.as_str()
+        ],
+        |row| row.get(0),
+    )
+}
+
+impl Store {
+    /// Persists a mint request as a queued job, or as a held one if it exceeds the mint limits of
+    /// its asset. The exceeded limit is recorded as the job error.
+    ///
+    /// Jobs are unique per deposit: a request for an already known deposit returns the existing
+    /// job, re-queueing it only if it previously failed. A failed job whose transaction is still
+    /// tracked as pending or committed reached the node, so it is restored instead.
+    pub async fn enqueue_mint_job(&self, args: &MintArgs) -> Result<MintJob, StoreError> {
+        let origin_network = args.deposit.origin_network;
+        let tx_hash = args.deposit.normalized_tx_hash();
+        let deposit_count = args.deposit.deposit_count;
+        let asset_origin_network = args.asset.origin_network;
+        let asset_origin_address = args.asset.origin_address.clone();
+        let asset_symbol = args.asset.asset_symbol.clone();
+        let asset_decimals = args.asset.decimals;
+        let amount = args.amount;
+        let recipient = args.note_recipient().unwrap_or_default().to_string();
+        let recipient_kind = args.recipient_kind();
+        let note_type = args.note_type.unwrap_or_default();
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                let tx = conn.transaction()?;
+                let known = tx
+                    .query_row(
+                        "SELECT mint_jobs.state, mint_jobs.amount, mint_jobs.recipient,
+                            tracked_transactions.status
+                         FROM mint_jobs LEFT JOIN tracked_transactions
+                            ON tracked_transactions.transaction_id = mint_jobs.transaction_id
+                         WHERE mint_jobs.origin_network = ?1 AND mint_jobs.tx_hash = ?2
+                            AND mint_jobs.deposit_count = ?3",
+                        params![origin_network, tx_hash, deposit_count],
+                        |row| {
+                            Ok(KnownJob {
+                                state: row.get(0)?,
+                                amount: row.get(1)?,
+                                recipient: row.get(2)?,
+                                transaction_status: row.get(3)?,
+                            })
+                        },
+                    )
+                    .optional()?;
+
+                // Only new and re-queued jobs are checked, with the amount of their first request
+                let (amount_to_check, recipient_to_check) = match &known {
+                    Some(job) => (job.amount, job.recipient.as_str()),
+                    None => (amount, recipient.as_str()),
+                };
+                let failed = MintJobState::Failed.as_str();
+                let pending = TrackedTransactionStatus::Pending.as_str();
+                let committed = TrackedTransactionStatus::Committed.as_str();
+                let mut state = MintJobState::Queued;
+                let mut error = None;
+                match &known {
+                    // Only failed jobs are updated, see the upsert below
+                    Some(job) if job.state != failed => {},
+                    // The job failed after its transaction reached the node, it is not re-minted
+                    Some(KnownJob { transaction_status: Some(status), .. })
+                        if status == pending =>
+                    {
+                        state = MintJobState::Submitted
+                    },
+                    Some(KnownJob { transaction_status: Some(status), .. })
+                        if status == committed =>
+                    {
+                        state = MintJobState::Committed
+                    },
+                    // New jobs, and failed ones whose transaction was discarded or never tracked.
+                    // The worker checks an untracked transaction against the client store before
+                    // minting again.
+                    _ => {
+                        let limits =
+                            query_mint_limits(&tx, asset_origin_network, &asset_origin_address)?;
+                        let since = now.saturating_sub(MINT_LIMIT_WINDOW_SECS);
+                        let asset_minted = minted_since(
+                            &tx,
+                            asset_origin_network,
+                            &asset_origin_address,
+                            None,
+                            since,
+                        )?;
+                        let recipient_minted = minted_since(
+                            &tx,
+                            asset_origin_network,
+                            &asset_origin_address,
+                            Some(recipient_to_check),
+                            since,
+                        )?;
+                        if let Some(exceeded) =
+                            limits.exceeded(amount_to_check, asset_minted, recipient_minted)
+                        {
+                            state = MintJobState::Held;
+                            error = Some(format!("Mint of {amount_to_check} {exceeded}"));
+                        }
+                    },
+                }
+
+                // A known deposit keeps the destination of its first request
+                tx.execute(
+                    "INSERT INTO mint_jobs (origin_network, tx_hash, deposit_count,
+                        asset_origin_network, asset_origin_address, asset_symbol, asset_decimals,
+                        amount, recipient, recipient_kind, note_type, state, error, created_at,
+                        updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?14, ?15, ?10, ?13, ?11, ?11)
+                     ON CONFLICT (origin_network, tx_hash, deposit_count) DO UPDATE
+                        SET state = ?10, error = ?13, updated_at = ?11
+                        WHERE state = ?12",
+                    params![
+                        origin_network,
+                        tx_hash,
+                        deposit_count,
+                        asset_origin_network,
+                        asset_origin_address,
+                        asset_symbol,
+                        asset_decimals,
+                        amount,
+                        recipient,
+                        state.as_str(),
+                        now,
+                        MintJobState::Failed.as_str(),
+                        error,
+                        recipient_kind.as_str(),
+                        note_type.as_str(),
+                    ],
+                )?;
+                let job = tx.query_row(
+                    &format!(
+                        "SELECT {MINT_JOB_COLUMNS} FROM {MINT_JOB_FROM}
+                         WHERE mint_jobs.origin_network = ?1 AND mint_jobs.tx_hash = ?2
+                            AND mint_jobs.deposit_count = ?3"
+                    ),
+                    params![origin_network, tx_hash, deposit_count],
+                    mint_job_from_row,
+                )?;
+                tx.commit()?;
+                Ok(job)
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    pub async fn get_mint_job(&self, id: u64) -> Result<Option<MintJob>, StoreError> {
+        self.pool
+            .conn(move |conn| {
+                match conn.query_row(
+                    &format!("SELECT {MINT_JOB_COLUMNS} FROM {MINT_JOB_FROM} WHERE mint_jobs.id = ?1"),
+                    params![id],
+                    mint_job_from_row,
+                ) {
+                    Ok(job) => Ok(Some(job)),
+                    Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                    Err(e) => Err(e),
+                }
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Returns the jobs the workers (re)process on start and after every sync, oldest first.
+    pub async fn get_unfinished_mint_jobs(&self) -> Result<Vec<MintJob>, StoreError> {
+        self.pool
+            .conn(move |conn| {
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT {MINT_JOB_COLUMNS} FROM {MINT_JOB_FROM}
+                     WHERE mint_jobs.state IN (?1, ?2) ORDER BY mint_jobs.id"
+                ))?;
+                stmt.query_map(
+                    params![MintJobState::Queued.as_str(), MintJobState::Executing.as_str()],
+                    mint_job_from_row,
+                )?
+                .collect()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Returns the submitted jobs whose transaction is still pending, oldest first.
+    pub async fn get_pending_mint_jobs(&self) -> Result<Vec<MintJob>, StoreError> {
+        self.pool
+            .conn(move |conn| {
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT {MINT_JOB_COLUMNS} FROM {MINT_JOB_FROM}
+                     WHERE mint_jobs.state = ?1 AND tracked_transactions.status = ?2
+                     ORDER BY mint_jobs.id"
+                ))?;
+                stmt.query_map(
+                    params![
+                        MintJobState::Submitted.as_str(),
+                        TrackedTransactionStatus::Pending.as_str()
+                    ],
+                    mint_job_from_row,
+                )?
+                .collect()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Counts the jobs in `state`.
+    pub async fn count_mint_jobs(&self, state: MintJobState) -> Result<u64, StoreError> {
+        self.pool
+            .conn(move |conn| {
+                conn.query_row(
+                    "SELECT COUNT(*) FROM mint_jobs WHERE state = ?1",
+                    params![state.as_str()],
+                    |row| row.get(0),
+                )
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Records the executed, but not yet submitted, transaction minting a batch of jobs, each
+    /// with its own note, and the last block it can be included in.
+    pub async fn set_mint_batch_executed(
+        &self,
+        job_notes: Vec<(u64, String)>,
+        faucet_id: String,
+        transaction_id: String,
+        expiration_block: u32,
+    ) -> Result<(), StoreError> {
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                let tx = conn.transaction()?;
+                for (id, note_id) in job_notes {
+                    tx.execute(
+                        "UPDATE mint_jobs
+                         SET state = ?2, faucet_id = ?3, note_id = ?4, transaction_id = ?5,
+                             expiration_block = ?6, error = NULL, updated_at = ?7
+                         WHERE id = ?1",
+                        params![
+                            id,
+                            MintJobState::Executing.as_str(),
+                            faucet_id,
+                            note_id,
+                            transaction_id,
+                            expiration_block,
+                            now
+                        ],
+                    )?;
+                }
+                tx.commit()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Moves a batch of executed jobs to the submitted state and starts tracking their
+    /// transaction, in a single database transaction.
+    ///
+    /// `prover` is `None` when the outcome of the submission is unknown, e.g. on a timeout after
+    /// the node may have accepted the transaction.
+    pub async fn set_mint_batch_submitted(
+        &self,
+        job_ids: Vec<u64>,
+        transaction_id: String,
+        prover: Option<ProverKind>,
+    ) -> Result<(), StoreError> {
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                let tx = conn.transaction()?;
+                for id in job_ids {
+                    tx.execute(
+                        "UPDATE mint_jobs SET state = ?2, error = NULL, updated_at = ?3
+                         WHERE id = ?1",
+                        params![id, MintJobState::Submitted.as_str(), now],
+                    )?;
+                }
+                tx.execute(
+                    "INSERT INTO tracked_transactions
+                     (transaction_id, status, submitted_at, updated_at, prover)
+                     VALUES (?1, ?2, ?3, ?3, ?4)
+                     ON CONFLICT (transaction_id) DO UPDATE SET prover = COALESCE(?4, prover)",
+                    params![
+                        transaction_id,
+                        TrackedTransactionStatus::Pending.as_str(),
+                        now,
+                        prover.map(|prover| prover.as_str())
+                    ],
+                )?;
+                tx.commit()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Fails jobs whose transaction never reached the node, e.g. because proving it failed, and
+    /// forgets the transaction so a retry mints them again right away.
+    pub async fn fail_unsent_mint_jobs(
+        &self,
+        job_ids: Vec<u64>,
+        error: String,
+    ) -> Result<(), StoreError> {
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                let tx = conn.transaction()?;
+                for id in job_ids {
+                    tx.execute(
+                        "UPDATE mint_jobs
+                         SET state = ?2, error = ?3, faucet_id = NULL, note_id = NULL,
+                             transaction_id = NULL, expiration_block = NULL, updated_at = ?4
+                         WHERE id = ?1",
+                        params![id, MintJobState::Failed.as_str(), error, now],
+                    )?;
+                }
+                tx.commit()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Tracks a transaction found on the node, or in the client store, after its jobs were left
+    /// executing, and moves them to the submitted state, or the committed one if `committed_block`
+    /// is set.
+    pub async fn recover_mint_transaction(
+        &self,
+        transaction_id: String,
+        committed_block: Option<u32>,
+    ) -> Result<(), StoreError> {
+        let now = now_seconds();
+        let (status, state) = match committed_block {
+            Some(_) => (TrackedTransactionStatus::Committed, MintJobState::Committed),
+            None => (TrackedTransactionStatus::Pending, MintJobState::Submitted),
+        };
+
+        self.pool
+            .conn_mut(move |conn| {
+                let tx = conn.transaction()?;
+                tx.execute(
+                    "INSERT INTO tracked_transactions
+                     (transaction_id, status, committed_block, submitted_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?4)
+                     ON CONFLICT (transaction_id) DO UPDATE
+                        SET status = ?2, committed_block = ?3, updated_at = ?4",
+                    params![transaction_id, status.as_str(), committed_block, now],
+                )?;
+                tx.execute(
+                    "UPDATE mint_jobs SET state = ?2, error = NULL, updated_at = ?3
+                     WHERE transaction_id = ?1",
+                    params![transaction_id, state.as_str(), now],
+                )?;
+                tx.commit()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    pub async fn set_mint_job_state(
+        &self,
+        id: u64,
+        state: MintJobState,
+        error: Option<String>,
+    ) -> Result<(), StoreError> {
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                conn.execute(
+                    "UPDATE mint_jobs SET state = ?2, error = ?3, updated_at = ?4 WHERE id = ?1",
+                    params![id, state.as_str(), error, now],
+                )
+                .map(|_| ())
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Moves a held job to `state`, either queued once approved or failed once rejected.
+    ///
+    /// Returns `false` if the job is not held, e.g. because another admin resolved it first.
+    pub async fn resolve_held_mint_job(
+        &self,
+        id: u64,
+        state: MintJobState,
+        error: Option<String>,
+    ) -> Result<bool, StoreError> {
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                conn.execute(
+                    "UPDATE mint_jobs SET state = ?2, error = ?3, updated_at = ?4
+                     WHERE id = ?1 AND state = ?5",
+                    params![id, state.as_str(), error, now, MintJobState::Held.as_str()],
+                )
+                .map(|updated| updated > 0)
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Store;
+    use crate::onchain::asset::Asset;
+    use crate::onchain::deposit::DepositId;
+    use crate::onchain::limits::MintLimits;
+    use crate::onchain::mint_job::MintJobState;
+    use crate::onchain::mint_note::{MintArgs, MintNoteType, RecipientKind};
+    use crate::store::test_store_path;
+
+    fn mint_args(tx_hash: &str) -> MintArgs {
+        MintArgs {
+            deposit: DepositId {
+                origin_network: 11155111,
+                tx_hash: tx_hash.to_string(),
+                deposit_count: 7,
+            },
+            asset: Asset {
+                origin_network: 0,
+                origin_address: "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238".to_string(),
+                asset_symbol: "USDC".to_string(),
+                decimals: 6,
+            },
+            amount: 1000,
+            recipient: Some("0x00".to_string()),
+            account_id: None,
+            note_type: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_deduplicate_jobs_by_deposit() {
+        let store = Store::new(test_store_path("dedup")).await.unwrap();
+
+        let job = store.enqueue_mint_job(&mint_args("0xAB")).await.unwrap();
+        let replayed = store.enqueue_mint_job(&mint_args("0xab")).await.unwrap();
+        assert_eq!(job.id, replayed.id);
+        assert_eq!(replayed.state, MintJobState::Queued);
+
+        store.set_mint_job_state(job.id, MintJobState::Failed, Some("boom".into())).await.unwrap();
+        let requeued = store.enqueue_mint_job(&mint_args("0xab")).await.unwrap();
+        assert_eq!(requeued.id, job.id);
+        assert_eq!(requeued.state, MintJobState::Queued);
+        assert_eq!(requeued.error, None);
+
+        let other = store.enqueue_mint_job(&mint_args("0xcd")).await.unwrap();
+        assert_ne!(other.id, job.id);
+        assert_eq!(store.get_unfinished_mint_jobs().await.unwrap().len(), 2);
+        assert_eq!(store.count_mint_jobs(MintJobState::Queued).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn should_not_requeue_a_job_whose_transaction_reached_the_node() {
+        let store = Store::new(test_store_path("requeue")).await.unwrap();
+        let submitted = store.enqueue_mint_job(&mint_args("0xab")).await.unwrap();
+        let discarded = store.enqueue_mint_job(&mint_args("0xcd")).await.unwrap();
+        for (job, transaction_id) in [(&submitted, "0xtx1"), (&discarded, "0xtx2")] {
+            store
+                .set_mint_batch_executed(
+                    vec![(job.id, "0xnote".into())],
+                    "0xfaucet".into(),
+                    transaction_id.into(),
+                    120,
+                )
+                .await
+                .unwrap();
+            store.add_tracked_transaction(transaction_id.into()).await.unwrap();
+            store.set_mint_job_state(job.id, MintJobState::Failed, Some("boom".into())).await.unwrap();
+        }
+        store.set_transaction_discarded("0xtx2".into(), "expired".into()).await.unwrap();
+
+        let retried = store.enqueue_mint_job(&mint_args("0xab")).await.unwrap();
+        assert_eq!(retried.state, MintJobState::Submitted);
+        assert_eq!(retried.error, None);
+        let retried = store.enqueue_mint_job(&mint_args("0xcd")).await.unwrap();
+        assert_eq!(retried.state, MintJobState::Queued);
+    }
+
+    #[tokio::test]
+    async fn should_persist_the_note_type() {
+        let store = Store::new(test_store_path("note_type")).await.unwrap();
+        let account_id = "0x8a65fc5a39e4cd106d648e3eb4ab5f";
+
+        let mut p2id = mint_args("0xAB");
+        p2id.recipient = None;
+        p2id.account_id = Some(account_id.to_string());
+        p2id.note_type = Some(MintNoteType::Public);
+        let job = store.enqueue_mint_job(&p2id).await.unwrap();
+        assert_eq!(job.note_type, MintNoteType::Public);
+        assert_eq!(job.recipient_kind, RecipientKind::Account);
+        assert_eq!(job.recipient, account_id);
+
+        // a replayed deposit keeps the destination of its first request
+        let replayed = store.enqueue_mint_job(&mint_args("0xab")).await.unwrap();
+        assert_eq!(replayed.id, job.id);
+        assert_eq!(replayed.note_type, MintNoteType::Public);
+        assert_eq!(replayed.recipient_kind, RecipientKind::Account);
+
+        let private = store.enqueue_mint_job(&mint_args("0xcd")).await.unwrap();
+        assert_eq!(private.note_type, MintNoteType::Private);
+        assert_eq!(private.recipient_kind, RecipientKind::Digest);
+        let stored = store.get_mint_job(job.id).await.unwrap().unwrap();
+        assert_eq!((stored.note_type, stored.recipient_kind), (job.note_type, job.recipient_kind));
+    }
+
+    #[tokio::test]
+    async fn should_record_a_batch_with_one_note_per_job() {
+        let store = Store::new(test_store_path("batch")).await.unwrap();
+        let first = store.enqueue_mint_job(&mint_args("0xab")).await.unwrap();
+        let second = store.enqueue_mint_job(&mint_args("0xcd")).await.unwrap();
+
+        store
+            .set_mint_batch_executed(
+                vec![(first.id, "0xnote1".into()), (second.id, "0xnote2".into())],
+                "0xfaucet".into(),
+                "0xtx".into(),
+                120,
+            )
+            .await
+            .unwrap();
+
+        for (id, note_id) in [(first.id, "0xnote1"), (second.id, "0xnote2")] {
+            let job = store.get_mint_job(id).await.unwrap().unwrap();
+            assert_eq!(job.state, MintJobState::Executing);
+            assert_eq!(job.note_id.as_deref(), Some(note_id));
+            assert_eq!(job.transaction_id.as_deref(), Some("0xtx"));
+            assert_eq!(job.expiration_block, Some(120));
+        }
+    }
+
+    #[tokio::test]
+    async fn should_recover_a_transaction_submitted_before_a_crash() {
+        let store = Store::new(test_store_path("recover")).await.unwrap();
+        let committed = store.enqueue_mint_job(&mint_args("0xab")).await.unwrap();
+        let pending = store.enqueue_mint_job(&mint_args("0xcd")).await.unwrap();
+        // the relayer stopped after the submission, before recording it
+        for (job, transaction_id) in [(&committed, "0xtx1"), (&pending, "0xtx2")] {
+            store
+                .set_mint_batch_executed(
+                    vec![(job.id, "0xnote".into())],
+                    "0xfaucet".into(),
+                    transaction_id.into(),
+                    120,
+                )
+                .await
+                .unwrap();
+        }
+        assert_eq!(store.get_unfinished_mint_jobs().await.unwrap().len(), 2);
+
+        // the node has the note of the first transaction, the second one may still be included
+        store.recover_mint_transaction("0xtx1".into(), Some(42)).await.unwrap();
+        store.recover_mint_transaction("0xtx2".into(), None).await.unwrap();
+        assert!(store.get_unfinished_mint_jobs().await.unwrap().is_empty());
+        assert_eq!(store.get_pending_transactions().await.unwrap().len(), 1);
+
+        let job = store.get_mint_job(committed.id).await.unwrap().unwrap();
+        assert_eq!((job.state, job.committed_block), (MintJobState::Committed, Some(42)));
+
+        // a replayed deposit returns the recovered job instead of minting it again
+        let replayed = store.enqueue_mint_job(&mint_args("0xcd")).await.unwrap();
+        assert_eq!(replayed.state, MintJobState::Submitted);
+        assert_eq!(replayed.minted_note().unwrap().transaction_id, "0xtx2");
+    }
+
+    #[tokio::test]
+    async fn should_tell_unsent_transactions_from_unknown_submissions() {
+        let store = Store::new(test_store_path("unknown_outcome")).await.unwrap();
+        let unsent = store.enqueue_mint_job(&mint_args("0xab")).await.unwrap();
+        let unknown = store.enqueue_mint_job(&mint_args("0xcd")).await.unwrap();
+        for (job, transaction_id) in [(&unsent, "0xtx1"), (&unknown, "0xtx2")] {
+            store
+                .set_mint_batch_executed(
+                    vec![(job.id, "0xnote".into())],
+                    "0xfaucet".into(),
+                    transaction_id.into(),
+                    120,
+                )
+                .await
+                .unwrap();
+        }
+
+        // proving the first transaction failed, the submission of the second one timed out
+        store.fail_unsent_mint_jobs(vec![unsent.id], "proving failed".into()).await.unwrap();
+        store.set_mint_batch_submitted(vec![unknown.id], "0xtx2".into(), None).await.unwrap();
+
+        let job = store.get_mint_job(unsent.id).await.unwrap().unwrap();
+        assert_eq!(job.state, MintJobState::Failed);
+        assert_eq!((job.transaction_id, job.expiration_block), (None, None));
+        let pending = store.get_pending_mint_jobs().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, unknown.id);
+        assert_eq!(pending[0].minted_note().unwrap().prover, None);
+
+        // the unsent job is minted again right away, the other one waits for its transaction
+        let replayed = store.enqueue_mint_job(&mint_args("0xab")).await.unwrap();
+        assert_eq!(replayed.state, MintJobState::Queued);
+        store.set_transaction_committed("0xtx2".into(), 42).await.unwrap();
+        assert!(store.get_pending_mint_jobs().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_hold_mints_over_the_limits() {
+        let store = Store::new(test_store_path("held_mints")).await.unwrap();
+        let asset = mint_args("0x00").asset;
+        let limits = MintLimits { daily_cap: Some(2500), ..MintLimits::default() };
+        store.set_mint_limits(asset.origin_network, &asset.origin_address, limits).await.unwrap();
+
+        let first = store.enqueue_mint_job(&mint_args("0x01")).await.unwrap();
+        let second = store.enqueue_mint_job(&mint_args("0x02")).await.unwrap();
+        assert_eq!(second.state, MintJobState::Queued);
+
+        let held = store.enqueue_mint_job(&mint_args("0x03")).await.unwrap();
+        assert_eq!(held.state, MintJobState::Held);
+        assert_eq!(held.error.as_deref(), Some("Mint of 1000 exceeds the 24h cap of 2500"));
+        assert_eq!(store.get_unfinished_mint_jobs().await.unwrap().len(), 2);
+        assert_eq!(store.count_mint_jobs(MintJobState::Held).await.unwrap(), 1);
+
+        // a replayed request stays held until an admin resolves it
+        let replayed = store.enqueue_mint_job(&mint_args("0x03")).await.unwrap();
+        assert_eq!(replayed.state, MintJobState::Held);
+        assert!(store.resolve_held_mint_job(held.id, MintJobState::Queued, None).await.unwrap());
+        assert!(!store.resolve_held_mint_job(held.id, MintJobState::Failed, None).await.unwrap());
+        assert!(!store.resolve_held_mint_job(first.id, MintJobState::Queued, None).await.unwrap());
+        assert_eq!(store.get_unfinished_mint_jobs().await.unwrap().len(), 3);
+        assert_eq!(store.count_mint_jobs(MintJobState::Held).await.unwrap(), 0);
+    }
+}
@@ -4,6 +4,15 @@ use miden_client::utils::{Deserializable, Serializable};
 use miden_objects::account::AccountId;
 use rusqlite::params;
 use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+mod exits;
+mod mint_jobs;
+mod registry;
+mod totals;
+mod transactions;
+
+pub use totals::AssetTotals;
 
 pub struct Store {
     pool: Pool,
@@ -11,8 +20,6 @@ pub struct Store {
 
 impl Store {
     pub async fn new(database_filepath: PathBuf) -> Result<Self, StoreError> {
-        let database_exists = database_filepath.exists();
-
         let pool = PoolBuilder::new()
             .path(database_filepath)
             .journal_mode(JournalMode::Wal)
@@ -20,11 +27,11 @@ impl Store {
             .await
             .map_err(|err| StoreError::DatabaseError(err.to_string()))?;
 
-        if !database_exists {
-            pool.conn_mut(|conn| conn.execute_batch(include_str!("store.sql")))
-                .await
-                .map_err(|err| StoreError::DatabaseError(err.to_string()))?;
-        }
+        // The schema only uses `CREATE TABLE IF NOT EXISTS`, so it is applied on every start to
+        // add the tables a database created by an older relayer, with only `assets_info`, lacks.
+        pool.conn_mut(|conn| conn.execute_batch(include_str!("store.sql")))
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))?;
 
         Ok(Self { pool })
     }
@@ -39,7 +46,8 @@ impl Store {
         let result = self.pool
             .conn(move |conn| {
                 let mut stmt = conn.prepare(
-                    "SELECT miden_faucet_id FROM assets_info WHERE origin_network = ?1 AND origin_address = ?2"
+                    "SELECT miden_faucet_id FROM assets_info
+                     WHERE origin_network = ?1 AND origin_address = ?2 COLLATE NOCASE"
                 )?;
                 match stmt.query_row(params![origin_network, origin_address], |row| {
                     let blob: Vec<u8> = row.get(0)?;
@@ -60,6 +68,22 @@ impl Store {
         Ok(result)
     }
 
+    /// Returns whether `faucet_id` is a faucet deployed or imported by the relayer.
+    pub async fn is_relayer_faucet(&self, faucet_id: AccountId) -> Result<bool, StoreError> {
+        let faucet_id_bytes = faucet_id.to_bytes();
+
+        self.pool
+            .conn(move |conn| {
+                conn.query_row(
+                    "SELECT EXISTS (SELECT 1 FROM assets_info WHERE miden_faucet_id = ?1)",
+                    params![faucet_id_bytes],
+                    |row| row.get(0),
+                )
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
     pub async fn add_faucet_id(
         &self,
         origin_network: u32,
@@ -84,3 +108,60 @@ impl Store {
         Ok(())
     }
 }
+
+/// Current unix time in seconds, used for the `created_at` / `updated_at` columns.
+fn now_seconds() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards").as_secs()
+}
+
+/// Returns a fresh database path in the system temp directory.
+#[cfg(test)]
+pub(crate) fn test_store_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("miden-tx-sender-{name}-{}.sql", rand::random::<u64>()))
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_client::utils::Serializable;
+    use miden_objects::account::AccountId;
+
+    use super::{Store, test_store_path};
+
+    #[tokio::test]
+    async fn should_open_a_database_with_only_the_faucets() {
+        let path = test_store_path("faucets-only");
+        let faucet_id = AccountId::from_hex("0x8a65fc5a39e4cd106d648e3eb4ab5f").unwrap();
+        {
+            // database of a relayer that only recorded its faucets
+            let conn = rusqlite::Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE assets_info
+                 (
+                     origin_network  INT  NOT NULL,
+                     origin_address  TEXT NOT NULL,
+                     miden_faucet_id BLOB NOT NULL,
+                     PRIMARY KEY (origin_network, origin_address)
+                 );",
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO assets_info VALUES (0, ?1, ?2)",
+                rusqlite::params![
+                    "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238",
+                    faucet_id.to_bytes()
+                ],
+            )
+            .unwrap();
+        }
+
+        let store = Store::new(path.clone()).await.unwrap();
+        let address = "0x1c7d4b196cb0c7b01d743fbc6116a902379c7238";
+        assert_eq!(store.get_faucet_id(0, address).await.unwrap(), Some(faucet_id));
+        assert!(store.get_unfinished_mint_jobs().await.unwrap().is_empty());
+
+        // reopening applies the schema again without touching the data
+        drop(store);
+        let store = Store::new(path).await.unwrap();
+        assert_eq!(store.get_faucet_id(0, address).await.unwrap(), Some(faucet_id));
+    }
+}
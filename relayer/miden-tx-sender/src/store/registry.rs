@@ -0,0 +1,266 @@
+use miden_client::store::StoreError;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+
+use super::{Store, now_seconds};
+use crate::onchain::asset::{Asset, AssetRegistration, RegisteredAsset};
+use crate::onchain::limits::MintLimits;
+
+const REGISTERED_ASSET_COLUMNS: &str = "origin_network, origin_address, asset_symbol, decimals,
+    max_supply, enabled, created_at, updated_at";
+
+fn registered_asset_from_row(row: &Row<'_>) -> rusqlite::Result<RegisteredAsset> {
+    Ok(RegisteredAsset {
+        asset: Asset {
+            origin_network: row.get(0)?,
+            origin_address: row.get(1)?,
+            asset_symbol: row.get(2)?,
+            decimals: row.get(3)?,
+        },
+        max_supply: row.get(4)?,
+        enabled: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+/// Reads the mint limits of an asset, none are enforced if it has no entry.
+pub(super) fn query_mint_limits(
+    conn: &Connection,
+    origin_network: u32,
+    origin_address: &str,
+) -> rusqlite::Result<MintLimits> {
+    conn.query_row(
+        "SELECT max_per_mint, daily_cap, recipient_daily_cap FROM asset_limits
+         WHERE origin_network = ?1 AND origin_address = ?2",
+        params![origin_network, origin_address],
+        |row| {
+            Ok(MintLimits {
+                max_per_mint: row.get(0)?,
+                daily_cap: row.get(1)?,
+                recipient_daily_cap: row.get(2)?,
+            })
+        },
+    )
+    .optional()
+    .map(Option::unwrap_or_default)
+}
+
+impl Store {
+    /// Adds an enabled asset to the registry.
+    ///
+    /// Returns `None` if the asset is already registered: its metadata is the one of an already
+    /// deployed faucet, so it is never overwritten.
+    pub async fn register_asset(
+        &self,
+        registration: AssetRegistration,
+    ) -> Result<Option<RegisteredAsset>, StoreError> {
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                let tx = conn.transaction()?;
+                let inserted = tx.execute(
+                    "INSERT INTO asset_registry (origin_network, origin_address, asset_symbol,
+                        decimals, max_supply, enabled, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, TRUE, ?6, ?6)
+                     ON CONFLICT (origin_network, origin_address) DO NOTHING",
+                    params![
+                        registration.asset.origin_network,
+                        registration.asset.origin_address,
+                        registration.asset.asset_symbol,
+                        registration.asset.decimals,
+                        registration.max_supply,
+                        now,
+                    ],
+                )?;
+                let asset = tx
+                    .query_row(
+                        &format!(
+                            "SELECT {REGISTERED_ASSET_COLUMNS} FROM asset_registry
+                             WHERE origin_network = ?1 AND origin_address = ?2"
+                        ),
+                        params![registration.asset.origin_network, registration.asset.origin_address],
+                        registered_asset_from_row,
+                    )
+                    .optional()?;
+                tx.commit()?;
+                Ok(asset.filter(|_| inserted > 0))
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Looks up a registered asset, the origin address is matched case insensitively.
+    pub async fn get_registered_asset(
+        &self,
+        origin_network: u32,
+        origin_address: &str,
+    ) -> Result<Option<RegisteredAsset>, StoreError> {
+        let origin_address = origin_address.to_string();
+
+        self.pool
+            .conn(move |conn| {
+                conn.query_row(
+                    &format!(
+                        "SELECT {REGISTERED_ASSET_COLUMNS} FROM asset_registry
+                         WHERE origin_network = ?1 AND origin_address = ?2"
+                    ),
+                    params![origin_network, origin_address],
+                    registered_asset_from_row,
+                )
+                .optional()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    pub async fn get_registered_assets(&self) -> Result<Vec<RegisteredAsset>, StoreError> {
+        self.pool
+            .conn(|conn| {
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT {REGISTERED_ASSET_COLUMNS} FROM asset_registry
+                     ORDER BY origin_network, origin_address"
+                ))?;
+                stmt.query_map([], registered_asset_from_row)?.collect()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    pub async fn get_mint_limits(
+        &self,
+        origin_network: u32,
+        origin_address: &str,
+    ) -> Result<MintLimits, StoreError> {
+        let origin_address = origin_address.to_string();
+
+        self.pool
+            .conn(move |conn| query_mint_limits(conn, origin_network, &origin_address))
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Replaces the mint limits of an asset. Only enforced on the requests received afterwards.
+    pub async fn set_mint_limits(
+        &self,
+        origin_network: u32,
+        origin_address: &str,
+        limits: MintLimits,
+    ) -> Result<(), StoreError> {
+        let origin_address = origin_address.to_string();
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                conn.execute(
+                    "INSERT INTO asset_limits (origin_network, origin_address, max_per_mint,
+                        daily_cap, recipient_daily_cap, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                     ON CONFLICT (origin_network, origin_address) DO UPDATE
+                        SET max_per_mint = ?3, daily_cap = ?4, recipient_daily_cap = ?5,
+                            updated_at = ?6",
+                    params![
+                        origin_network,
+                        origin_address,
+                        limits.max_per_mint,
+                        limits.daily_cap,
+                        limits.recipient_daily_cap,
+                        now,
+                    ],
+                )
+                .map(|_| ())
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Enables or disables the mints of a registered asset, returning `None` if it is unknown.
+    pub async fn set_asset_enabled(
+        &self,
+        origin_network: u32,
+        origin_address: &str,
+        enabled: bool,
+    ) -> Result<Option<RegisteredAsset>, StoreError> {
+        let origin_address = origin_address.to_string();
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                conn.query_row(
+                    &format!(
+                        "UPDATE asset_registry SET enabled = ?3, updated_at = ?4
+                         WHERE origin_network = ?1 AND origin_address = ?2
+                         RETURNING {REGISTERED_ASSET_COLUMNS}"
+                    ),
+                    params![origin_network, origin_address, enabled, now],
+                    registered_asset_from_row,
+                )
+                .optional()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Store;
+    use crate::onchain::asset::{Asset, AssetRegistration};
+    use crate::onchain::limits::MintLimits;
+    use crate::store::test_store_path;
+
+    fn registration() -> AssetRegistration {
+        AssetRegistration {
+            asset: Asset {
+                origin_network: 0,
+                origin_address: "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238".to_string(),
+                asset_symbol: "USDC".to_string(),
+                decimals: 6,
+            },
+            max_supply: 1_000_000_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_register_and_disable_assets() {
+        let store = Store::new(test_store_path("registry")).await.unwrap();
+        let lowercase = "0x1c7d4b196cb0c7b01d743fbc6116a902379c7238";
+
+        let registered = store.register_asset(registration()).await.unwrap().unwrap();
+        assert!(registered.enabled);
+        assert_eq!(registered.asset, registration().asset);
+
+        // the canonical metadata is never overwritten
+        let mut conflicting = registration();
+        conflicting.asset.origin_address = lowercase.to_string();
+        conflicting.asset.decimals = 18;
+        assert_eq!(store.register_asset(conflicting).await.unwrap(), None);
+
+        let disabled = store.set_asset_enabled(0, lowercase, false).await.unwrap().unwrap();
+        assert!(!disabled.enabled);
+        assert_eq!(disabled.asset.decimals, 6);
+        assert_eq!(store.get_registered_asset(0, lowercase).await.unwrap(), Some(disabled));
+
+        assert_eq!(store.get_registered_asset(1, lowercase).await.unwrap(), None);
+        assert_eq!(store.set_asset_enabled(1, lowercase, true).await.unwrap(), None);
+        assert_eq!(store.get_registered_assets().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn should_replace_mint_limits() {
+        let store = Store::new(test_store_path("limits")).await.unwrap();
+        let address = "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238";
+        assert_eq!(store.get_mint_limits(0, address).await.unwrap(), MintLimits::default());
+
+        let limits = MintLimits {
+            max_per_mint: Some(1000),
+            daily_cap: Some(5000),
+            recipient_daily_cap: None,
+        };
+        store.set_mint_limits(0, address, limits).await.unwrap();
+        assert_eq!(store.get_mint_limits(0, &address.to_lowercase()).await.unwrap(), limits);
+
+        store.set_mint_limits(0, address, MintLimits::default()).await.unwrap();
+        assert_eq!(store.get_mint_limits(0, address).await.unwrap(), MintLimits::default());
+    }
+}
@@ -1,7 +1,135 @@
-CREATE TABLE assets_info
+CREATE TABLE IF NOT EXISTS assets_info
 (
     origin_network  INT  NOT NULL,
     origin_address  TEXT NOT NULL,
     miden_faucet_id BLOB NOT NULL,
     PRIMARY KEY (origin_network, origin_address)
 );
+
+CREATE TABLE IF NOT EXISTS mint_jobs
+(
+    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+    origin_network       INT  NOT NULL,
+    tx_hash              TEXT NOT NULL,
+    deposit_count        INT  NOT NULL,
+    asset_origin_network INT  NOT NULL,
+    asset_origin_address TEXT NOT NULL,
+    asset_symbol         TEXT NOT NULL,
+    asset_decimals       INT  NOT NULL,
+    amount               INT  NOT NULL,
+    recipient            TEXT NOT NULL,
+    recipient_kind       TEXT NOT NULL,
+    note_type            TEXT NOT NULL,
+    state                TEXT NOT NULL,
+    faucet_id            TEXT,
+    note_id              TEXT,
+    transaction_id       TEXT,
+    expiration_block     INT,
+    error                TEXT,
+    created_at           INT  NOT NULL,
+    updated_at           INT  NOT NULL,
+    UNIQUE (origin_network, tx_hash, deposit_count)
+);
+
+CREATE INDEX IF NOT EXISTS mint_jobs_state_idx ON mint_jobs (state);
+
+CREATE TABLE IF NOT EXISTS tracked_transactions
+(
+    transaction_id  TEXT PRIMARY KEY,
+    status          TEXT NOT NULL,
+    committed_block INT,
+    discard_reason  TEXT,
+    submitted_at    INT  NOT NULL,
+    updated_at      INT  NOT NULL,
+    prover          TEXT
+);
+
+CREATE INDEX IF NOT EXISTS tracked_transactions_status_idx ON tracked_transactions (status);
+
+CREATE TABLE IF NOT EXISTS exit_events
+(
+    id                INTEGER PRIMARY KEY AUTOINCREMENT,
+    note_id           TEXT NOT NULL UNIQUE,
+    block_number      INT  NOT NULL,
+    origin_network    INT  NOT NULL,
+    origin_address    TEXT NOT NULL,
+    asset_symbol      TEXT NOT NULL,
+    decimals          INT  NOT NULL,
+    receiver          TEXT NOT NULL,
+    destination_chain INT  NOT NULL,
+    amount            INT  NOT NULL,
+    call_address      TEXT,
+    call_data         TEXT,
+    created_at        INT  NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS exit_events_block_number_idx ON exit_events (block_number);
+
+-- Bridge notes whose sender could not be resolved when their block was scanned, retried on every
+-- scan once their sender is a faucet of the relayer
+CREATE TABLE IF NOT EXISTS unresolved_exit_notes
+(
+    note_id      TEXT PRIMARY KEY,
+    block_number INT  NOT NULL,
+    sender       BLOB NOT NULL,
+    created_at   INT  NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS unresolved_exit_notes_sender_idx ON unresolved_exit_notes (sender);
+
+-- Single row holding the last block whose bridge notes were scanned into exit_events
+CREATE TABLE IF NOT EXISTS exit_scan_cursor
+(
+    id           INTEGER PRIMARY KEY CHECK (id = 0),
+    block_number INT NOT NULL,
+    updated_at   INT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS exit_deliveries
+(
+    consumer        TEXT NOT NULL,
+    exit_id         INT  NOT NULL REFERENCES exit_events (id),
+    status          TEXT NOT NULL,
+    attempts        INT  NOT NULL,
+    delivered_at    INT  NOT NULL,
+    acknowledged_at INT,
+    PRIMARY KEY (consumer, exit_id)
+);
+
+-- Per consumer position: every exit event up to exit_id is acknowledged
+CREATE TABLE IF NOT EXISTS exit_cursors
+(
+    consumer   TEXT PRIMARY KEY,
+    exit_id    INT NOT NULL,
+    updated_at INT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS exit_events_destination_chain_idx ON exit_events (destination_chain, id);
+CREATE INDEX IF NOT EXISTS exit_events_origin_idx
+    ON exit_events (origin_network, origin_address COLLATE NOCASE, id);
+CREATE INDEX IF NOT EXISTS exit_events_receiver_idx ON exit_events (receiver COLLATE NOCASE, id);
+
+CREATE TABLE IF NOT EXISTS asset_registry
+(
+    origin_network INT     NOT NULL,
+    origin_address TEXT    NOT NULL COLLATE NOCASE,
+    asset_symbol   TEXT    NOT NULL,
+    decimals       INT     NOT NULL,
+    max_supply     INT     NOT NULL,
+    enabled        BOOLEAN NOT NULL,
+    created_at     INT     NOT NULL,
+    updated_at     INT     NOT NULL,
+    PRIMARY KEY (origin_network, origin_address)
+);
+
+-- Mint limits of a registered asset, in base units. NULL limits are not enforced
+CREATE TABLE IF NOT EXISTS asset_limits
+(
+    origin_network      INT  NOT NULL,
+    origin_address      TEXT NOT NULL COLLATE NOCASE,
+    max_per_mint        INT,
+    daily_cap           INT,
+    recipient_daily_cap INT,
+    updated_at          INT  NOT NULL,
+    PRIMARY KEY (origin_network, origin_address)
+);
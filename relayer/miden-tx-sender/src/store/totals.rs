@@ -0,0 +1,137 @@
+use miden_client::store::StoreError;
+use rusqlite::params;
+
+use super::Store;
+use crate::onchain::asset::Asset;
+use crate::onchain::mint_job::MintJobState;
+
+/// Amounts of an asset moved through the bridge, as recorded by the relayer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetTotals {
+    pub asset: Asset,
+    /// Sum of the submitted and committed mint jobs.
+    pub minted: u64,
+    /// Part of `minted` whose transactions are not committed yet.
+    pub pending: u64,
+    /// Sum of the observed exits.
+    pub burned: u64,
+}
+
+impl Store {
+    /// Returns the minted and burned totals of every asset the relayer has seen, keyed by the
+    /// lowercase origin address.
+    pub async fn get_asset_totals(&self) -> Result<Vec<AssetTotals>, StoreError> {
+        self.pool
+            .conn(|conn| {
+                let mut stmt = conn.prepare(
+                    "SELECT origin_network, LOWER(origin_address), MAX(asset_symbol), MAX(decimals),
+                            SUM(minted), SUM(pending), SUM(burned)
+                     FROM (
+                         SELECT asset_origin_network AS origin_network,
+                                asset_origin_address AS origin_address, asset_symbol,
+                                asset_decimals AS decimals, amount AS minted,
+                                IIF(state = ?1, amount, 0) AS pending, 0 AS burned
+                         FROM mint_jobs WHERE state IN (?1, ?2)
+                         UNION ALL
+                         SELECT origin_network, origin_address, asset_symbol, decimals, 0, 0, amount
+                         FROM exit_events
+                     )
+                     GROUP BY origin_network, LOWER(origin_address)
+                     ORDER BY origin_network, LOWER(origin_address)",
+                )?;
+                let states =
+                    params![MintJobState::Submitted.as_str(), MintJobState::Committed.as_str()];
+                stmt.query_map(states, |row| {
+                    Ok(AssetTotals {
+                        asset: Asset {
+                            origin_network: row.get(0)?,
+                            origin_address: row.get(1)?,
+                            asset_symbol: row.get(2)?,
+                            decimals: row.get(3)?,
+                        },
+                        minted: row.get(4)?,
+                        pending: row.get(5)?,
+                        burned: row.get(6)?,
+                    })
+                })?
+                .collect()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Checks that the database accepts writes by taking its write lock.
+    pub async fn check_writable(&self) -> Result<(), StoreError> {
+        self.pool
+            .conn(|conn| conn.execute_batch("BEGIN IMMEDIATE; ROLLBACK;"))
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Store;
+    use crate::onchain::asset::Asset;
+    use crate::onchain::deposit::DepositId;
+    use crate::onchain::mint_job::MintJobState;
+    use crate::onchain::mint_note::MintArgs;
+    use crate::onchain::poll_events::ExitEvent;
+    use crate::store::test_store_path;
+
+    const USDC: &str = "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238";
+
+    fn usdc() -> Asset {
+        Asset {
+            origin_network: 0,
+            origin_address: USDC.to_string(),
+            asset_symbol: "USDC".to_string(),
+            decimals: 6,
+        }
+    }
+
+    fn mint_args(deposit_count: u32, amount: u64) -> MintArgs {
+        MintArgs {
+            deposit: DepositId {
+                origin_network: 11155111,
+                tx_hash: "0xab".to_string(),
+                deposit_count,
+            },
+            asset: Asset { origin_address: USDC.to_lowercase(), ..usdc() },
+            amount,
+            recipient: Some("0x00".to_string()),
+            account_id: None,
+            note_type: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn should_total_minted_and_burned_amounts_per_asset() {
+        let store = Store::new(test_store_path("totals")).await.unwrap();
+        store.check_writable().await.unwrap();
+
+        let submitted = store.enqueue_mint_job(&mint_args(1, 1000)).await.unwrap();
+        store.set_mint_job_state(submitted.id, MintJobState::Submitted, None).await.unwrap();
+        let failed = store.enqueue_mint_job(&mint_args(2, 500)).await.unwrap();
+        store.set_mint_job_state(failed.id, MintJobState::Failed, None).await.unwrap();
+        // queued jobs are not minted yet
+        store.enqueue_mint_job(&mint_args(3, 200)).await.unwrap();
+
+        let exit = ExitEvent {
+            note_id: "0x01".to_string(),
+            block_number: 10,
+            asset: usdc(),
+            receiver: "0xab348cb6a2bf1ae152c793e091ff0545cf0ad7b7".to_string(),
+            destination_chain: 11155111,
+            amount: 300,
+            call_address: None,
+            call_data: None,
+        };
+        store.add_exit_events(vec![exit], vec![], 10).await.unwrap();
+
+        let totals = store.get_asset_totals().await.unwrap();
+        assert_eq!(totals.len(), 1);
+        assert_eq!(totals[0].asset.origin_address, USDC.to_lowercase());
+        assert_eq!((totals[0].minted, totals[0].pending, totals[0].burned), (1000, 1000, 300));
+    }
+}
@@ -0,0 +1,230 @@
+use std::str::FromStr;
+
+use miden_client::store::StoreError;
+use rusqlite::{Row, params};
+
+use super::{Store, now_seconds};
+use crate::onchain::mint_job::MintJobState;
+use crate::onchain::prover::ProverKind;
+use crate::onchain::tracker::{TrackedTransaction, TrackedTransactionStatus};
+
+const TRACKED_TRANSACTION_COLUMNS: &str =
+    "transaction_id, status, committed_block, discard_reason, submitted_at, updated_at, prover";
+
+fn tracked_transaction_from_row(row: &Row<'_>) -> rusqlite::Result<TrackedTransaction> {
+    let status: String = row.get(1)?;
+    let status = TrackedTransactionStatus::from_str(&status).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, e.into())
+    })?;
+    let prover: Option<String> = row.get(6)?;
+    let prover = prover.map(|prover| ProverKind::from_str(&prover)).transpose().map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(6, rusqlite::types::Type::Text, e.into())
+    })?;
+
+    Ok(TrackedTransaction {
+        transaction_id: row.get(0)?,
+        status,
+        committed_block: row.get(2)?,
+        discard_reason: row.get(3)?,
+        submitted_at: row.get(4)?,
+        updated_at: row.get(5)?,
+        prover,
+    })
+}
+
+impl Store {
+    /// Starts tracking a submitted transaction.
+    pub async fn add_tracked_transaction(&self, transaction_id: String) -> Result<(), StoreError> {
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                conn.execute(
+                    "INSERT INTO tracked_transactions
+                     (transaction_id, status, submitted_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?3)
+                     ON CONFLICT (transaction_id) DO NOTHING",
+                    params![transaction_id, TrackedTransactionStatus::Pending.as_str(), now],
+                )
+                .map(|_| ())
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    pub async fn get_tracked_transaction(
+        &self,
+        transaction_id: String,
+    ) -> Result<Option<TrackedTransaction>, StoreError> {
+        self.pool
+            .conn(move |conn| {
+                match conn.query_row(
+                    &format!(
+                        "SELECT {TRACKED_TRANSACTION_COLUMNS} FROM tracked_transactions
+                         WHERE transaction_id = ?1"
+                    ),
+                    params![transaction_id],
+                    tracked_transaction_from_row,
+                ) {
+                    Ok(tx) => Ok(Some(tx)),
+                    Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                    Err(e) => Err(e),
+                }
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    pub async fn get_pending_transactions(&self) -> Result<Vec<TrackedTransaction>, StoreError> {
+        self.pool
+            .conn(move |conn| {
+                let mut stmt = conn.prepare(&format!(
+                    "SELECT {TRACKED_TRANSACTION_COLUMNS} FROM tracked_transactions
+                     WHERE status = ?1"
+                ))?;
+                stmt.query_map(
+                    params![TrackedTransactionStatus::Pending.as_str()],
+                    tracked_transaction_from_row,
+                )?
+                .collect()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Marks the transaction as committed, moving the mint jobs it belongs to along.
+    pub async fn set_transaction_committed(
+        &self,
+        transaction_id: String,
+        block_number: u32,
+    ) -> Result<(), StoreError> {
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                let tx = conn.transaction()?;
+                tx.execute(
+                    "UPDATE tracked_transactions
+                     SET status = ?2, committed_block = ?3, updated_at = ?4
+                     WHERE transaction_id = ?1",
+                    params![
+                        transaction_id,
+                        TrackedTransactionStatus::Committed.as_str(),
+                        block_number,
+                        now
+                    ],
+                )?;
+                tx.execute(
+                    "UPDATE mint_jobs SET state = ?2, updated_at = ?3 WHERE transaction_id = ?1",
+                    params![transaction_id, MintJobState::Committed.as_str(), now],
+                )?;
+                tx.commit()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+
+    /// Marks the transaction as discarded and fails the mint jobs it belongs to, so a repeated
+    /// mint request re-queues them.
+    pub async fn set_transaction_discarded(
+        &self,
+        transaction_id: String,
+        reason: String,
+    ) -> Result<(), StoreError> {
+        let now = now_seconds();
+
+        self.pool
+            .conn_mut(move |conn| {
+                let tx = conn.transaction()?;
+                tx.execute(
+                    "UPDATE tracked_transactions
+                     SET status = ?2, discard_reason = ?3, updated_at = ?4
+                     WHERE transaction_id = ?1",
+                    params![
+                        transaction_id,
+                        TrackedTransactionStatus::Discarded.as_str(),
+                        reason,
+                        now
+                    ],
+                )?;
+                tx.execute(
+                    "UPDATE mint_jobs SET state = ?2, error = ?3, updated_at = ?4
+                     WHERE transaction_id = ?1",
+                    params![
+                        transaction_id,
+                        MintJobState::Failed.as_str(),
+                        format!("Transaction discarded: {reason}"),
+                        now
+                    ],
+                )?;
+                tx.commit()
+            })
+            .await
+            .map_err(|err| StoreError::DatabaseError(err.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Store;
+    use crate::onchain::asset::Asset;
+    use crate::onchain::deposit::DepositId;
+    use crate::onchain::mint_job::MintJobState;
+    use crate::onchain::mint_note::MintArgs;
+    use crate::onchain::prover::ProverKind;
+    use crate::onchain::tracker::TrackedTransactionStatus;
+    use crate::store::test_store_path;
+
+    #[tokio::test]
+    async fn should_commit_mint_job_with_its_transaction() {
+        let store = Store::new(test_store_path("tracker")).await.unwrap();
+        let job = store
+            .enqueue_mint_job(&MintArgs {
+                deposit: DepositId {
+                    origin_network: 11155111,
+                    tx_hash: "0xab".to_string(),
+                    deposit_count: 1,
+                },
+                asset: Asset {
+                    origin_network: 0,
+                    origin_address: "0x1c7D4B196Cb0C7B01d743Fbc6116a902379C7238".to_string(),
+                    asset_symbol: "USDC".to_string(),
+                    decimals: 6,
+                },
+                amount: 1000,
+                recipient: Some("0x00".to_string()),
+                account_id: None,
+                note_type: None,
+            })
+            .await
+            .unwrap();
+
+        store
+            .set_mint_batch_executed(
+                vec![(job.id, "0xnote".into())],
+                "0xfaucet".into(),
+                "0xtx".into(),
+                120,
+            )
+            .await
+            .unwrap();
+        store
+            .set_mint_batch_submitted(vec![job.id], "0xtx".into(), Some(ProverKind::Remote))
+            .await
+            .unwrap();
+        assert_eq!(store.get_pending_transactions().await.unwrap().len(), 1);
+
+        store.set_transaction_committed("0xtx".into(), 42).await.unwrap();
+
+        let tx = store.get_tracked_transaction("0xtx".into()).await.unwrap().unwrap();
+        assert_eq!(tx.status, TrackedTransactionStatus::Committed);
+        assert_eq!(tx.committed_block, Some(42));
+        assert_eq!(tx.prover, Some(ProverKind::Remote));
+        assert!(store.get_pending_transactions().await.unwrap().is_empty());
+
+        let job = store.get_mint_job(job.id).await.unwrap().unwrap();
+        assert_eq!(job.state, MintJobState::Committed);
+        assert_eq!(job.committed_block, Some(42));
+        assert_eq!(job.minted_note().unwrap().prover, Some(ProverKind::Remote));
+    }
+}
@@ -1,5 +0,0 @@
-
-
-pub mod origin;
-pub mod metadata;
-pub mod errors;
\ No newline at end of file